@@ -1,35 +1,46 @@
+use crate::profile;
 use std::env;
 
 use cookie::Cookie;
 use cookie_store::CookieStore;
 use url::Url;
 
-pub fn download(year_no: usize, day_no: usize) {
-    println!("Downloading input...");
-    // create dir
-    let dirname = format!("input/year{:04}", year_no);
-    std::fs::create_dir_all(dirname).unwrap();
-
-    let dl_url = format!("https://adventofcode.com/{}/day/{}/input", year_no, day_no);
+// AOC_BASE_URL points the downloads to another server, e.g. a local one
+// serving fixtures.
+fn base_url() -> String {
+    env::var("AOC_BASE_URL").unwrap_or_else(|_| String::from("https://adventofcode.com"))
+}
 
-    let session_cookie = Cookie::new("session", env::var("AOC_SESSION").unwrap());
+// GET relative to the base URL. The session cookie of the profile is only
+// sent if set, inputs need it, puzzle pages without it only show part 1.
+pub fn get(path: &str, profile: Option<&str>) -> Result<String, String> {
+    let base_url = base_url();
+    let base = Url::parse(&base_url).map_err(|e| format!("invalid base URL: {}", e))?;
     let mut store = CookieStore::default();
-    store
-        .insert_raw(
-            &session_cookie,
-            &Url::parse("https://adventofcode.com/").unwrap(),
-        )
-        .unwrap();
+    if let Ok(session) = env::var(profile::session_var(profile)) {
+        store
+            .insert_raw(&Cookie::new("session", session), &base)
+            .map_err(|e| e.to_string())?;
+    }
 
     let agent = ureq::builder().cookie_store(store).build();
-    let body = agent
-        .get(&dl_url)
+    let url = format!("{}{}", base_url.trim_end_matches('/'), path);
+    agent
+        .get(&url)
         .set("User-Agent", &env::var("USER_AGENT").unwrap())
         .call()
-        .unwrap()
+        .map_err(|e| format!("{}: {}", url, e))?
         .into_string()
-        .unwrap();
+        .map_err(|e| e.to_string())
+}
+
+pub fn download(profile: Option<&str>, year_no: usize, day_no: usize) -> Result<(), String> {
+    let session_var = profile::session_var(profile);
+    env::var(&session_var).map_err(|_| format!("no input and {} not set", session_var))?;
+    println!("Downloading input...");
+    let body = get(&format!("/{}/day/{}/input", year_no, day_no), profile)?;
 
-    let filename = format!("input/year{:04}/day{:02}.input", year_no, day_no);
-    std::fs::write(filename, body).unwrap();
+    let path = profile::path(profile, year_no, day_no, "input");
+    std::fs::create_dir_all(path.parent().unwrap()).map_err(|e| e.to_string())?;
+    std::fs::write(&path, body).map_err(|e| format!("{}: {}", path.display(), e))
 }
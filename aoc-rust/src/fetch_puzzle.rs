@@ -0,0 +1,337 @@
+use crate::{dl_input, profile};
+use common::fixture::{self, Fixture};
+use std::path::Path;
+
+// Downloads the puzzle page (cached as input/yearYYYY/dayDD.html), keeps
+// the puzzle text as Markdown next to it, and writes the first example of
+// each part with the highlighted answer as fixture. The answers are a
+// guess (the last highlighted code in each part), so check the fixtures.
+// Once solved, the page also shows the accepted answers, which are stored
+// as input/yearYYYY/dayDD.answers for the status command.
+
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct Part {
+    pub title: Option<String>,
+    // contents of the <pre><code> blocks
+    pub examples: Vec<String>,
+    // contents of <code><em>, i.e. highlighted values
+    pub highlights: Vec<String>,
+    pub text: String,
+}
+
+impl Part {
+    pub fn answer(&self) -> Option<&str> {
+        self.highlights.last().map(|s| s.as_str())
+    }
+}
+
+pub fn fetch(profile: Option<&str>, year_no: usize, day_no: usize) {
+    let filename = profile::path(profile, year_no, day_no, "html");
+    std::fs::create_dir_all(filename.parent().unwrap()).unwrap();
+
+    let html = match std::fs::read_to_string(&filename) {
+        Ok(html) => html,
+        Err(_) => {
+            println!("Downloading puzzle...");
+            let html = dl_input::get(&format!("/{}/day/{}", year_no, day_no), profile)
+                .unwrap_or_else(|e| panic!("Download failed: {}", e));
+            std::fs::write(&filename, &html).unwrap();
+            html
+        }
+    };
+
+    let parts = parse_parts(&html);
+    if parts.is_empty() {
+        println!("No puzzle text found in {}", filename.display());
+        return;
+    }
+    let text: Vec<_> = parts.iter().map(|p| p.text.as_str()).collect();
+    let text_filename = profile::path(profile, year_no, day_no, "md");
+    std::fs::write(&text_filename, text.join("\n")).unwrap();
+    println!("Puzzle text: {}", text_filename.display());
+
+    let answers = puzzle_answers(&html);
+    if !answers.is_empty() {
+        let answers_filename = profile::path(profile, year_no, day_no, "answers");
+        std::fs::write(&answers_filename, crate::status::format_answers(&answers)).unwrap();
+        println!("Answers: {}", answers_filename.display());
+    }
+
+    let dir = fixture::dir(year_no, day_no);
+    std::fs::create_dir_all(&dir).unwrap();
+    for fixture in fixtures(&parts) {
+        let path = dir.join(format!("{}.txt", fixture.name));
+        write_fixture(&path, &fixture);
+    }
+}
+
+fn write_fixture(path: &Path, fixture: &Fixture) {
+    // never overwrite, the answers may have been corrected by hand
+    if path.exists() {
+        println!("Exists, skipped: {}", path.display());
+    } else {
+        std::fs::write(path, fixture.to_string()).unwrap();
+        println!("Example: {}", path.display());
+    }
+}
+
+// Part 1 gives ex1. Part 2 mostly reuses that example; if it brings a new
+// one, that becomes ex2.
+pub fn fixtures(parts: &[Part]) -> Vec<Fixture> {
+    let mut fixtures: Vec<Fixture> = vec![];
+    for (i, part) in parts.iter().enumerate().take(2) {
+        let answer = part.answer().map(|s| s.to_string());
+        match part.examples.first() {
+            Some(input) => {
+                let mut fixture = Fixture::new(&format!("ex{}", fixtures.len() + 1), input);
+                if i == 0 {
+                    fixture.star1 = answer;
+                } else {
+                    fixture.star2 = answer;
+                }
+                fixtures.push(fixture);
+            }
+            None => {
+                if let Some(fixture) = fixtures.first_mut() {
+                    fixture.star2 = answer;
+                }
+            }
+        }
+    }
+    fixtures
+}
+
+// The accepted answers, shown after each solved part.
+pub fn puzzle_answers(html: &str) -> Vec<String> {
+    const PREFIX: &str = "Your puzzle answer was <code>";
+    html.match_indices(PREFIX)
+        .filter_map(|(i, _)| {
+            let rest = &html[i + PREFIX.len()..];
+            rest.find("</code>")
+                .map(|end| decode_entities(&rest[..end]))
+        })
+        .collect()
+}
+
+enum Node<'a> {
+    Open(&'a str, &'a str),
+    Close(&'a str),
+    Text(&'a str),
+}
+
+fn nodes(html: &str) -> Vec<Node<'_>> {
+    let mut nodes = vec![];
+    let mut rest = html;
+    while let Some(start) = rest.find('<') {
+        if start > 0 {
+            nodes.push(Node::Text(&rest[..start]));
+        }
+        let end = match rest[start..].find('>') {
+            Some(end) => start + end,
+            None => break,
+        };
+        let tag = &rest[start + 1..end];
+        if let Some(name) = tag.strip_prefix('/') {
+            nodes.push(Node::Close(name.trim()));
+        } else if !tag.starts_with('!') {
+            let tag = tag.trim_end_matches('/');
+            let (name, attrs) = tag.split_once(char::is_whitespace).unwrap_or((tag, ""));
+            nodes.push(Node::Open(name, attrs));
+        }
+        rest = &rest[end + 1..];
+    }
+    if !rest.is_empty() {
+        nodes.push(Node::Text(rest));
+    }
+    nodes
+}
+
+fn decode_entities(s: &str) -> String {
+    let mut out = String::new();
+    let mut rest = s;
+    while let Some(start) = rest.find('&') {
+        out.push_str(&rest[..start]);
+        rest = &rest[start..];
+        let entity = rest.find(';').map(|end| (&rest[1..end], end));
+        let decoded = entity.and_then(|(name, end)| {
+            let c = match name {
+                "lt" => Some('<'),
+                "gt" => Some('>'),
+                "amp" => Some('&'),
+                "quot" => Some('"'),
+                "apos" => Some('\''),
+                _ => name
+                    .strip_prefix("#x")
+                    .and_then(|n| u32::from_str_radix(n, 16).ok())
+                    .or_else(|| name.strip_prefix('#').and_then(|n| n.parse().ok()))
+                    .and_then(char::from_u32),
+            };
+            c.map(|c| (c, end))
+        });
+        match decoded {
+            Some((c, end)) => {
+                out.push(c);
+                rest = &rest[end + 1..];
+            }
+            None => {
+                out.push('&');
+                rest = &rest[1..];
+            }
+        }
+    }
+    out.push_str(rest);
+    out
+}
+
+// One part per <article class="day-desc">.
+pub fn parse_parts(html: &str) -> Vec<Part> {
+    let mut parts = vec![];
+    let mut part: Option<Part> = None;
+    let (mut in_pre, mut in_code, mut in_em, mut in_h2) = (false, false, false, false);
+    let mut example = String::new();
+    let mut highlight = String::new();
+    let mut title = String::new();
+
+    for node in nodes(html) {
+        let Some(p) = part.as_mut() else {
+            if let Node::Open("article", attrs) = node {
+                if attrs.contains("day-desc") {
+                    part = Some(Part::default());
+                }
+            }
+            continue;
+        };
+        match node {
+            Node::Open(name, _) => {
+                match name {
+                    "pre" => {
+                        in_pre = true;
+                        p.text.push_str("```\n");
+                    }
+                    "code" => in_code = true,
+                    "em" => in_em = true,
+                    "h2" => in_h2 = true,
+                    "li" => p.text.push_str("- "),
+                    _ => {}
+                }
+                if !in_pre && name == "code" {
+                    p.text.push('`');
+                }
+                if !in_pre && name == "em" {
+                    p.text.push('*');
+                }
+            }
+            Node::Close(name) => {
+                if !in_pre && name == "code" {
+                    p.text.push('`');
+                }
+                if !in_pre && name == "em" {
+                    p.text.push('*');
+                }
+                if in_code && in_em && !in_pre && (name == "code" || name == "em") {
+                    p.highlights.push(std::mem::take(&mut highlight));
+                }
+                match name {
+                    "pre" => {
+                        in_pre = false;
+                        p.examples.push(std::mem::take(&mut example));
+                        if !p.text.ends_with('\n') {
+                            p.text.push('\n');
+                        }
+                        p.text.push_str("```\n\n");
+                    }
+                    "code" => in_code = false,
+                    "em" => in_em = false,
+                    "h2" => {
+                        in_h2 = false;
+                        let t = title.trim().trim_matches('-').trim();
+                        p.title = Some(t.to_string());
+                        p.text = format!("## {}\n\n", t);
+                        title.clear();
+                    }
+                    "p" | "ul" => p.text.push_str("\n\n"),
+                    "li" => p.text.push('\n'),
+                    "article" => {
+                        parts.push(part.take().unwrap());
+                    }
+                    _ => {}
+                }
+            }
+            Node::Text(text) => {
+                let text = decode_entities(text);
+                if in_h2 {
+                    title.push_str(&text);
+                    continue;
+                }
+                if in_pre {
+                    example.push_str(&text);
+                } else if in_code && in_em {
+                    highlight.push_str(&text);
+                }
+                p.text.push_str(&text);
+            }
+        }
+    }
+    parts
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PAGE: &str = r#"<html><body><main>
+<article class="day-desc"><h2>--- Day 1: Historian Hysteria ---</h2>
+<p>For example:</p>
+<pre><code>3   4
+4   3
+2   &lt;5
+</code></pre>
+<p>Pair <em>them</em> up, the distance is <code>2</code>. Total: <code><em>11</em></code>.</p>
+<ul><li>one</li></ul>
+</article>
+<p>Your puzzle answer was <code>1234</code>.</p>
+<article class="day-desc"><h2 id="part2">--- Part Two ---</h2>
+<p>Now the similarity score is <em><code>31</code></em>.</p>
+</article>
+</main></body></html>"#;
+
+    #[test]
+    fn test_parse_parts() {
+        let parts = parse_parts(PAGE);
+        assert_eq!(parts.len(), 2);
+        assert_eq!(parts[0].title.as_deref(), Some("Day 1: Historian Hysteria"));
+        assert_eq!(parts[0].examples, vec!["3   4\n4   3\n2   <5\n"]);
+        assert_eq!(parts[0].answer(), Some("11"));
+        assert_eq!(parts[1].answer(), Some("31"));
+        assert!(parts[0]
+            .text
+            .starts_with("## Day 1: Historian Hysteria\n\n"));
+        assert!(parts[0].text.contains("```\n3   4\n"));
+        assert!(parts[0].text.contains("Total: `*11*`."));
+        assert!(parts[0].text.contains("- one\n"));
+    }
+
+    #[test]
+    fn test_fixtures() {
+        let fixtures = fixtures(&parse_parts(PAGE));
+        assert_eq!(fixtures.len(), 1);
+        assert_eq!(
+            fixtures[0].to_string(),
+            "star1: 11\nstar2: 31\n---\n3   4\n4   3\n2   <5\n"
+        );
+    }
+
+    #[test]
+    fn test_puzzle_answers() {
+        assert_eq!(puzzle_answers(PAGE), vec!["1234"]);
+        assert!(puzzle_answers("<p>Answer: <input/></p>").is_empty());
+    }
+
+    #[test]
+    fn test_entities() {
+        assert_eq!(
+            decode_entities("a &lt;b&gt; &amp; &#39;c&#x27; &x"),
+            "a <b> & 'c' &x"
+        );
+    }
+}
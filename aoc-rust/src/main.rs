@@ -1,43 +1,60 @@
 mod dl_input;
+mod fetch_puzzle;
+mod profile;
+mod status;
+mod watch;
 
+use common::day::Prepared;
+use common::params::Params;
 use common::year::Year;
+use solutions::{get_year, YEAR_MAX};
 use std::io::ErrorKind;
-use std::time::Instant;
+use std::ops::RangeInclusive;
+use std::panic::{self, AssertUnwindSafe};
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{self, RecvTimeoutError};
+use std::sync::Arc;
+use std::thread;
+use std::time::{Duration, Instant};
+use util::cancel::{self, CancelToken};
+use util::viz;
 
 use dotenv::dotenv;
 
-const YEAR_MAX: usize = 2025;
-
-fn parse_year(args: &[String]) -> Option<Box<dyn Year>> {
-    match args.len() {
-        1..=2 => get_year(YEAR_MAX),
-        3 => {
-            let year_no = args[1]
-                .parse::<usize>()
-                .unwrap_or_else(|_| panic!("Invalid year argument: {}", args[1]));
-            get_year(year_no)
-        }
+fn parse_year(args: &[String]) -> Result<Box<dyn Year>, String> {
+    let year_no = match args.len() {
+        1..=2 => YEAR_MAX,
+        3 => args[1]
+            .parse::<usize>()
+            .unwrap_or_else(|_| panic!("Invalid year argument: {}", args[1])),
         _ => panic!("Invalid number of arguments!"),
-    }
+    };
+    get_year(year_no).map_err(|e| not_found(year_no, 0, e))
 }
 
-fn get_year(year_no: usize) -> Option<Box<dyn Year>> {
-    match year_no {
-        2015 => Some(Box::new(year2015::Year2015 {})),
-        2016 => Some(Box::new(year2016::Year2016 {})),
-        2017 => Some(Box::new(year2017::Year2017 {})),
-        2018 => Some(Box::new(year2018::Year2018 {})),
-        2019 => Some(Box::new(year2019::Year2019 {})),
-        2020 => Some(Box::new(year2020::Year2020 {})),
-        2021 => Some(Box::new(year2021::Year2021 {})),
-        2022 => Some(Box::new(year2022::Year2022 {})),
-        2023 => Some(Box::new(year2023::Year2023 {})),
-        2024 => Some(Box::new(year2024::Year2024 {})),
-        2025 => Some(Box::new(year2025::Year2025 {})),
-        _ => None,
+fn not_found(year_no: usize, day_no: usize, e: solutions::Error) -> String {
+    match e {
+        solutions::Error::NotCompiledIn => format!("Year {} not compiled in!", year_no),
+        solutions::Error::NoSuchYear => format!("Year {} not implemented!", year_no),
+        _ => format!("Day {} not implemented!", day_no),
     }
 }
 
+// --param overrides must be declared by one of the days they apply to,
+// checked before any of them runs.
+fn check_params(
+    params: &Params,
+    year_no: usize,
+    day_nos: RangeInclusive<usize>,
+) -> Result<(), String> {
+    let declared: Vec<_> = day_nos
+        .filter_map(|day_no| solutions::get_day(year_no, day_no).ok())
+        .flat_map(|day| day.params())
+        .collect();
+    params.check(&declared)
+}
+
 #[allow(clippy::borrowed_box)]
 fn get_day_no_max(year: &Box<dyn Year>) -> usize {
     let mut day_no = 0;
@@ -47,81 +64,462 @@ fn get_day_no_max(year: &Box<dyn Year>) -> usize {
     day_no
 }
 
-#[allow(clippy::borrowed_box)]
-fn run_day(year: &Box<dyn Year>, day_no: usize) {
-    if let Some(day) = year.get_day(day_no) {
-        println!("Year: {:04}, Day: {:02}", year.year_no(), day_no);
-        let input = load_input(year.year_no(), day_no);
-
-        for star_no in 1..3 {
-            let start = Instant::now();
-            let res = match star_no {
-                1 => day.star1(&input),
-                2 => day.star2(&input),
-                _ => format!("invalid star_no: {}", star_no),
-            };
+// Runner options from the command line flags.
+#[derive(Clone, Default)]
+struct Options {
+    params: Params,
+    // see profile.rs, None for the default profile
+    profile: Option<String>,
+    // time budget per star, see run_day_with_timeout
+    timeout: Option<Duration>,
+    // status as Markdown
+    markdown: bool,
+}
+
+impl Options {
+    fn all_profiles(&self) -> bool {
+        self.profile.as_deref() == Some(profile::ALL)
+    }
+
+    fn profile(&self) -> Option<&str> {
+        self.profile.as_deref()
+    }
+}
+
+// time a cancelled worker gets to stop
+const CANCEL_GRACE: Duration = Duration::from_secs(1);
+
+// set once a worker kept running after its cancel
+static ABANDONED: AtomicBool = AtomicBool::new(false);
+
+enum Event {
+    Parsed(Duration),
+    ParseError(String),
+    Star(usize, String, Duration),
+    Timeout(usize, Duration),
+    Panic(usize),
+    // not run, as an earlier worker ignored the cancel
+    Skipped(usize),
+}
+
+fn print_event(event: &Event) {
+    match event {
+        Event::Parsed(time) => println!("parse   time: {:>10} µs", time.as_micros()),
+        Event::ParseError(e) => println!("parse error: {}", e),
+        Event::Star(star_no, res, time) => {
             let res = if res.find('\n').is_some() {
                 format!("\n{}", res)
             } else {
-                res
+                res.clone()
             };
             println!(
                 "star {}  time: {:>10} µs    res: {}",
                 star_no,
-                start.elapsed().as_micros(),
+                time.as_micros(),
                 res
             );
         }
+        Event::Timeout(star_no, time) => println!(
+            "star {}  time: {:>10} µs    res: TIMEOUT",
+            star_no,
+            time.as_micros()
+        ),
+        // the panic message has been printed by the worker
+        Event::Panic(star_no) => println!("star {}  res: PANIC", star_no),
+        Event::Skipped(star_no) => println!("star {}  res: SKIPPED", star_no),
+    }
+}
+
+// Solves the stars from_star..=2, reporting each step as it is done.
+#[allow(clippy::borrowed_box)]
+fn solve_day(
+    year: &Box<dyn Year>,
+    day_no: usize,
+    input: &str,
+    params: &Params,
+    from_star: usize,
+    report: &mut dyn FnMut(Event),
+) {
+    let day = year.get_day(day_no).unwrap();
+    let start = Instant::now();
+    let prepared = match day.prepare(input) {
+        Ok(prepared) => prepared,
+        Err(e) => return report(Event::ParseError(e)),
+    };
+    if let Prepared::Parsed(_) = prepared {
+        if from_star == 1 {
+            report(Event::Parsed(start.elapsed()));
+        }
+    }
+
+    for star_no in from_star..3 {
+        viz::begin(&format!(
+            "year{:04}_day{:02}_star{}",
+            year.year_no(),
+            day_no,
+            star_no
+        ));
+        let start = Instant::now();
+        let res = prepared.solve(day.as_ref(), star_no, params);
+        viz::finish();
+        report(Event::Star(star_no, res, start.elapsed()));
+    }
+}
+
+#[allow(clippy::borrowed_box)]
+fn run_day(year: &Box<dyn Year>, day_no: usize, options: &Options) {
+    if options.all_profiles() {
+        profile::run_day(year.year_no(), day_no, options);
+    } else if year.get_day(day_no).is_some() {
+        println!("Year: {:04}, Day: {:02}", year.year_no(), day_no);
+        let input = match load_input(options.profile(), year.year_no(), day_no) {
+            Ok(input) => input,
+            Err(e) => return println!("Error: {}", e),
+        };
+        solve(year.year_no(), day_no, input, options, &mut |event| {
+            print_event(&event)
+        });
     } else {
         println!("Day {} not implemented!", day_no);
     }
 }
 
+fn solve(
+    year_no: usize,
+    day_no: usize,
+    input: String,
+    options: &Options,
+    report: &mut dyn FnMut(Event),
+) {
+    match options.timeout {
+        Some(timeout) => {
+            run_day_with_timeout(year_no, day_no, input, &options.params, timeout, report)
+        }
+        None => {
+            let year = get_year(year_no).unwrap();
+            let mut star_no = 1;
+            while star_no <= 2 {
+                // a star panicking doesn't keep the next one from running
+                let mut next = star_no;
+                let res = panic::catch_unwind(AssertUnwindSafe(|| {
+                    solve_day(
+                        &year,
+                        day_no,
+                        &input,
+                        &options.params,
+                        star_no,
+                        &mut |event| {
+                            match event {
+                                Event::ParseError(_) => next = 3,
+                                Event::Star(n, _, _) => next = n + 1,
+                                _ => {}
+                            }
+                            report(event);
+                        },
+                    )
+                }));
+                if res.is_err() {
+                    report(Event::Panic(next));
+                    next += 1;
+                }
+                star_no = next;
+            }
+        }
+    }
+}
+
+// Both answers, with PANIC or TIMEOUT for failed stars, and the total time.
+fn answers(
+    year_no: usize,
+    day_no: usize,
+    input: String,
+    options: &Options,
+) -> (Vec<String>, Duration) {
+    let mut results = vec![String::from("PANIC"); 2];
+    let mut time = Duration::ZERO;
+    solve(year_no, day_no, input, options, &mut |event| match event {
+        Event::Parsed(t) => time += t,
+        Event::ParseError(e) => results = vec![format!("parse error: {}", e); 2],
+        Event::Star(star_no, res, t) => {
+            results[star_no - 1] = res;
+            time += t;
+        }
+        Event::Timeout(star_no, t) => {
+            results[star_no - 1] = String::from("TIMEOUT");
+            time += t;
+        }
+        Event::Panic(_) => {}
+        Event::Skipped(star_no) => results[star_no - 1] = String::from("SKIPPED"),
+    });
+    (results, time)
+}
+
+// Solves the day in a worker thread. A star exceeding the timeout (star 1
+// including the parsing) is cancelled and reported as TIMEOUT, and a new
+// worker goes on with the next star. Solutions only stop early where they
+// check util::cancel; a worker still running shortly after the cancel
+// would slow down everything after it, so all remaining stars of the run
+// are skipped then.
+fn run_day_with_timeout(
+    year_no: usize,
+    day_no: usize,
+    input: String,
+    params: &Params,
+    timeout: Duration,
+    report: &mut dyn FnMut(Event),
+) {
+    let input = Arc::new(input);
+    let mut star_no = 1;
+    while star_no <= 2 {
+        if ABANDONED.load(Ordering::Relaxed) {
+            report(Event::Skipped(star_no));
+            star_no += 1;
+            continue;
+        }
+        let token = CancelToken::new();
+        let (tx, rx) = mpsc::channel();
+        {
+            let (token, input, params) = (token.clone(), input.clone(), params.clone());
+            thread::spawn(move || {
+                cancel::install(token);
+                cancel::run(|| {
+                    let year = get_year(year_no).unwrap();
+                    solve_day(&year, day_no, &input, &params, star_no, &mut |event| {
+                        tx.send(event).ok();
+                    })
+                });
+            });
+        }
+
+        let mut start = Instant::now();
+        loop {
+            match rx.recv_timeout(timeout.saturating_sub(start.elapsed())) {
+                Ok(event) => {
+                    let parse_error = matches!(event, Event::ParseError(_));
+                    if let Event::Star(n, _, _) = event {
+                        star_no = n + 1;
+                        start = Instant::now();
+                    }
+                    report(event);
+                    if parse_error {
+                        return;
+                    }
+                }
+                Err(RecvTimeoutError::Timeout) => {
+                    token.cancel();
+                    report(Event::Timeout(star_no, start.elapsed()));
+                    star_no += 1;
+                    if !exits(&rx) {
+                        eprintln!("Worker ignores the cancel, skipping the rest of the run");
+                        ABANDONED.store(true, Ordering::Relaxed);
+                    }
+                    break;
+                }
+                Err(RecvTimeoutError::Disconnected) => {
+                    if star_no <= 2 {
+                        report(Event::Panic(star_no));
+                        star_no += 1;
+                    }
+                    break;
+                }
+            }
+        }
+    }
+}
+
+// Whether a cancelled worker ends within CANCEL_GRACE, dropping its
+// sender.
+fn exits(rx: &mpsc::Receiver<Event>) -> bool {
+    let start = Instant::now();
+    loop {
+        match rx.recv_timeout(CANCEL_GRACE.saturating_sub(start.elapsed())) {
+            Ok(_) => {}
+            Err(RecvTimeoutError::Disconnected) => return true,
+            Err(RecvTimeoutError::Timeout) => return false,
+        }
+    }
+}
+
 #[allow(clippy::borrowed_box)]
-fn run_all(year: &Box<dyn Year>) {
+fn run_all(year: &Box<dyn Year>, options: &Options) {
+    if options.all_profiles() {
+        return profile::run_all(year.year_no(), 1..=get_day_no_max(year), options);
+    }
     let start_all = Instant::now();
     for day_no in 0..get_day_no_max(year) {
-        run_day(year, day_no + 1);
+        run_day(year, day_no + 1, options);
     }
     println!("\ntotal   time: {:>10} µs", start_all.elapsed().as_micros());
 }
 
+fn parse_flags(flags: &[String]) -> Options {
+    let mut options = Options::default();
+    let mut viz_config: Option<viz::Config> = None;
+    let mut stride = 1;
+    for flag in flags {
+        let (name, value) = match flag.split_once('=') {
+            Some((name, value)) => (name, Some(value)),
+            None => (flag.as_str(), None),
+        };
+        match (name, value) {
+            ("--visualize", None) => {
+                viz_config = Some(viz::Config::default());
+            }
+            ("--visualize", Some(value)) => {
+                let (format, dir) = value
+                    .split_once(':')
+                    .unwrap_or_else(|| panic!("Invalid visualize output: {}", value));
+                let format = match format {
+                    "txt" => viz::FrameFormat::Text,
+                    "ppm" => viz::FrameFormat::Ppm,
+                    "gif" => viz::FrameFormat::Gif,
+                    _ => panic!("Invalid frame format: {}", format),
+                };
+                viz_config = Some(viz::Config {
+                    output: viz::Output::Dir(PathBuf::from(dir), format),
+                    ..Default::default()
+                });
+            }
+            ("--visualize-stride", Some(value)) => {
+                stride = value
+                    .parse::<usize>()
+                    .unwrap_or_else(|_| panic!("Invalid stride: {}", value));
+            }
+            ("--param", Some(value)) => {
+                options
+                    .params
+                    .set(value)
+                    .unwrap_or_else(|e| panic!("{}", e));
+            }
+            ("--markdown", None) => options.markdown = true,
+            ("--profile", Some(value)) => options.profile = Some(value.to_string()),
+            ("--timeout", Some(value)) => {
+                let secs = value
+                    .parse::<f64>()
+                    .ok()
+                    .filter(|secs| *secs > 0.0)
+                    .unwrap_or_else(|| panic!("Invalid timeout: {}", value));
+                options.timeout = Some(Duration::from_secs_f64(secs));
+            }
+            _ => panic!("Invalid flag: {}", flag),
+        }
+    }
+    // an abandoned star would keep recording into the next one's frames
+    if viz_config.is_some() && options.timeout.is_some() {
+        panic!("--visualize can't be combined with --timeout");
+    }
+    if let Some(config) = viz_config {
+        viz::enable(viz::Config { stride, ..config });
+    }
+    options
+}
+
+// Joins "--param key=value" into "--param=key=value", same for --profile.
+fn join_param_args(args: impl Iterator<Item = String>) -> Vec<String> {
+    let mut joined = vec![];
+    let mut args = args.peekable();
+    while let Some(arg) = args.next() {
+        match (arg.as_str(), args.peek()) {
+            ("--param" | "--profile", Some(value)) if !value.starts_with("--") => {
+                joined.push(format!("{}={}", arg, value));
+                args.next();
+            }
+            _ => joined.push(arg),
+        }
+    }
+    joined
+}
+
 fn main() {
     dotenv().ok();
 
-    let args: Vec<_> = std::env::args().collect();
-    if let Some(year) = parse_year(&args) {
-        if args.len() == 1 {
-            run_day(&year, get_day_no_max(&year));
-        } else {
-            match args[args.len() - 1].parse::<usize>() {
-                Ok(d) => run_day(&year, d),
-                Err(_) => match args[args.len() - 1].as_ref() {
-                    "all" => {
-                        run_all(&year);
-                    }
-                    _ => {
-                        println!("Invalid parameter: {}", args[1]);
+    let (flags, args): (Vec<_>, Vec<_>) = join_param_args(std::env::args())
+        .into_iter()
+        .partition(|a| a.starts_with("--"));
+    let options = parse_flags(&flags);
+    let command = args.get(1).map(|a| a.as_str());
+    if options.all_profiles() && matches!(command, Some("fetch-puzzle" | "status" | "watch")) {
+        println!("--profile={} only works for running days", profile::ALL);
+        return;
+    }
+    match command {
+        Some("fetch-puzzle") => {
+            match parse_year_day(&args[2..]) {
+                Some((year_no, day_no)) => fetch_puzzle::fetch(options.profile(), year_no, day_no),
+                None => println!("Usage: fetch-puzzle [year] day"),
+            }
+            return;
+        }
+        Some("status") => {
+            status::status_cmd(&args[2..], &options);
+            return;
+        }
+        Some("watch") => {
+            match parse_year_day(&args[2..]) {
+                Some((year_no, day_no)) => {
+                    match check_params(&options.params, year_no, day_no..=day_no) {
+                        Ok(()) => watch::watch(year_no, day_no, &options),
+                        Err(e) => println!("Error: {}", e),
                     }
-                },
+                }
+                None => println!("Usage: watch [year] day"),
             }
+            return;
         }
-    } else {
-        println!("Year {} not implemented!", args[1]);
+        _ => {}
     }
-}
-
-fn load_input(year_no: usize, day_no: usize) -> String {
-    let filename = format!("input/year{:04}/day{:02}.input", year_no, day_no);
-    match std::fs::read_to_string(&filename) {
-        Ok(s) => s,
-        Err(e) => {
-            if e.kind() == ErrorKind::NotFound {
-                dl_input::download(year_no, day_no);
-                std::fs::read_to_string(&filename).unwrap()
+    match parse_year(&args) {
+        Ok(year) => {
+            let day_no_max = get_day_no_max(&year);
+            // None for all days
+            let day_no = if args.len() == 1 {
+                Some(day_no_max)
             } else {
-                panic!("Error: {}", e);
+                match args[args.len() - 1].parse::<usize>() {
+                    Ok(d) => Some(d),
+                    Err(_) => match args[args.len() - 1].as_ref() {
+                        "all" => None,
+                        _ => return println!("Invalid parameter: {}", args[1]),
+                    },
+                }
+            };
+            let day_nos = day_no.map_or(1..=day_no_max, |d| d..=d);
+            if let Err(e) = check_params(&options.params, year.year_no(), day_nos) {
+                return println!("Error: {}", e);
+            }
+            match day_no {
+                Some(d) => run_day(&year, d, &options),
+                None => run_all(&year, &options),
             }
         }
+        Err(e) => println!("{}", e),
+    }
+}
+
+// "[year] day" of the fetch-puzzle and watch commands
+fn parse_year_day(args: &[String]) -> Option<(usize, usize)> {
+    let numbers: Vec<_> = args
+        .iter()
+        .map(|a| {
+            a.parse::<usize>()
+                .unwrap_or_else(|_| panic!("Invalid argument: {}", a))
+        })
+        .collect();
+    match numbers[..] {
+        [day_no] => Some((YEAR_MAX, day_no)),
+        [year_no, day_no] => Some((year_no, day_no)),
+        _ => None,
+    }
+}
+
+fn load_input(profile: Option<&str>, year_no: usize, day_no: usize) -> Result<String, String> {
+    let path = profile::path(profile, year_no, day_no, "input");
+    match std::fs::read_to_string(&path) {
+        Ok(s) => Ok(s),
+        Err(e) if e.kind() == ErrorKind::NotFound => {
+            dl_input::download(profile, year_no, day_no)?;
+            std::fs::read_to_string(&path).map_err(|e| format!("{}: {}", path.display(), e))
+        }
+        Err(e) => Err(format!("{}: {}", path.display(), e)),
     }
 }
@@ -0,0 +1,246 @@
+use crate::{answers, load_input, not_found, Options};
+use std::env;
+use std::path::PathBuf;
+
+// Inputs of several AoC accounts, to check the solutions against all of
+// them. The default profile keeps its files in input/yearYYYY and its
+// session cookie in AOC_SESSION; a profile "alice" uses input/alice/yearYYYY
+// and AOC_SESSION_ALICE.
+
+// --profile=all runs all profiles side by side
+pub const ALL: &str = "all";
+
+pub fn path(profile: Option<&str>, year_no: usize, day_no: usize, extension: &str) -> PathBuf {
+    let mut path = PathBuf::from("input");
+    if let Some(profile) = profile {
+        path.push(profile);
+    }
+    path.join(format!("year{:04}", year_no))
+        .join(format!("day{:02}.{}", day_no, extension))
+}
+
+pub fn session_var(profile: Option<&str>) -> String {
+    match profile {
+        Some(profile) => format!(
+            "AOC_SESSION_{}",
+            profile.to_uppercase().replace(['-', '.'], "_")
+        ),
+        None => String::from("AOC_SESSION"),
+    }
+}
+
+// All profiles with inputs or a session cookie, the default one first.
+pub fn list() -> Vec<Option<String>> {
+    let vars: Vec<String> = env::vars()
+        .map(|(var, _)| var)
+        .filter(|var| var.starts_with("AOC_SESSION_"))
+        .collect();
+    let mut default = env::var("AOC_SESSION").is_ok();
+    let mut dirs = vec![];
+    if let Ok(entries) = std::fs::read_dir("input") {
+        for entry in entries.filter_map(|e| e.ok()) {
+            if !entry.path().is_dir() {
+                continue;
+            }
+            let name = entry.file_name().to_string_lossy().to_string();
+            if name.starts_with("year") {
+                default = true;
+            } else {
+                dirs.push(name);
+            }
+        }
+    }
+    let named = names(dirs, &vars).into_iter().map(Some);
+    if default {
+        std::iter::once(None).chain(named).collect()
+    } else {
+        named.collect()
+    }
+}
+
+// The input directories, and the session variables without one; "bob-2"
+// and AOC_SESSION_BOB_2 are the same profile.
+fn names(mut dirs: Vec<String>, vars: &[String]) -> Vec<String> {
+    for var in vars {
+        if !dirs.iter().any(|dir| session_var(Some(dir)) == *var) {
+            let name = var.strip_prefix("AOC_SESSION_").unwrap_or(var);
+            dirs.push(name.to_lowercase());
+        }
+    }
+    dirs.sort();
+    dirs
+}
+
+fn name(profile: &Option<String>) -> &str {
+    profile.as_deref().unwrap_or("default")
+}
+
+fn failed(res: &str) -> bool {
+    res == "PANIC" || res == "TIMEOUT" || res.starts_with("parse error")
+}
+
+// Runs the day for all profiles and prints the answers side by side.
+// Returns the profiles it fails on, if it doesn't fail on all of them.
+pub fn run_day(year_no: usize, day_no: usize, options: &Options) -> Vec<String> {
+    if let Err(e) = solutions::get_day(year_no, day_no) {
+        println!("{}", not_found(year_no, day_no, e));
+        return vec![];
+    }
+    println!("Year: {:04}, Day: {:02}", year_no, day_no);
+    let profiles = list();
+    let mut results = vec![];
+    for profile in &profiles {
+        let options = Options {
+            profile: profile.clone(),
+            ..options.clone()
+        };
+        let res = match load_input(profile.as_deref(), year_no, day_no) {
+            Ok(input) => Some(answers(year_no, day_no, input, &options).0),
+            Err(e) => {
+                println!("{}: {}", name(profile), e);
+                None
+            }
+        };
+        results.push(res);
+    }
+    let names: Vec<_> = profiles.iter().map(|p| name(p).to_string()).collect();
+    print!("{}", format_table(&names, &results));
+
+    let failing = failing(&names, &results);
+    if !failing.is_empty() {
+        println!("failing on {} only", failing.join(", "));
+    }
+    failing
+}
+
+pub fn run_all(year_no: usize, day_nos: impl Iterator<Item = usize>, options: &Options) {
+    let mut failing = vec![];
+    for day_no in day_nos {
+        let profiles = run_day(year_no, day_no, options);
+        if !profiles.is_empty() {
+            failing.push(format!("day {}: {}", day_no, profiles.join(", ")));
+        }
+    }
+    if !failing.is_empty() {
+        println!("\nFailing on some profiles only:");
+        for line in failing {
+            println!("  {}", line);
+        }
+    }
+}
+
+// Profiles without an input don't count.
+fn failing(names: &[String], results: &[Option<Vec<String>>]) -> Vec<String> {
+    let failed: Vec<_> = results
+        .iter()
+        .map(|res| {
+            res.as_ref()
+                .map(|answers| answers.iter().any(|a| failed(a)))
+        })
+        .collect();
+    let run = failed.iter().flatten().count();
+    let failing: Vec<_> = names
+        .iter()
+        .zip(&failed)
+        .filter(|(_, failed)| **failed == Some(true))
+        .map(|(name, _)| name.clone())
+        .collect();
+    if failing.len() < run {
+        failing
+    } else {
+        vec![]
+    }
+}
+
+pub fn format_table(names: &[String], results: &[Option<Vec<String>>]) -> String {
+    // answers drawn as letters on one line
+    let cell = |res: &Option<Vec<String>>, star: usize| match res {
+        Some(answers) => answers[star].replace('\n', "\\n"),
+        None => String::from("-"),
+    };
+    let widths: Vec<_> = names
+        .iter()
+        .zip(results)
+        .map(|(name, res)| {
+            (0..2)
+                .map(|star| cell(res, star).chars().count())
+                .fold(name.len(), usize::max)
+        })
+        .collect();
+    let row = |label: &str, cells: Vec<String>| {
+        let cells: Vec<_> = cells
+            .iter()
+            .zip(&widths)
+            .map(|(c, w)| format!("{:<w$}", c, w = w))
+            .collect();
+        format!("{:<8}{}", label, cells.join("  ").trim_end()) + "\n"
+    };
+    let mut table = row("", names.to_vec());
+    for star in 0..2 {
+        let cells = results.iter().map(|res| cell(res, star)).collect();
+        table += &row(&format!("star {}", star + 1), cells);
+    }
+    table
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn results() -> (Vec<String>, Vec<Option<Vec<String>>>) {
+        let names = vec![
+            String::from("default"),
+            String::from("alice"),
+            String::from("bob"),
+        ];
+        let results = vec![
+            Some(vec![String::from("22"), String::from("6,1")]),
+            Some(vec![String::from("318"), String::from("PANIC")]),
+            None,
+        ];
+        (names, results)
+    }
+
+    #[test]
+    fn test_path() {
+        assert_eq!(
+            path(None, 2024, 8, "input"),
+            PathBuf::from("input/year2024/day08.input")
+        );
+        assert_eq!(
+            path(Some("alice"), 2024, 8, "answers"),
+            PathBuf::from("input/alice/year2024/day08.answers")
+        );
+        assert_eq!(session_var(None), "AOC_SESSION");
+        assert_eq!(session_var(Some("bob-2")), "AOC_SESSION_BOB_2");
+    }
+
+    #[test]
+    fn test_names() {
+        let dirs = vec![String::from("bob-2"), String::from("alice")];
+        let vars = vec![
+            String::from("AOC_SESSION_BOB_2"),
+            String::from("AOC_SESSION_CAROL"),
+        ];
+        assert_eq!(names(dirs, &vars), vec!["alice", "bob-2", "carol"]);
+    }
+
+    #[test]
+    fn test_table() {
+        let (names, results) = results();
+        assert_eq!(
+            format_table(&names, &results),
+            "        default  alice  bob\n\
+             star 1  22       318    -\n\
+             star 2  6,1      PANIC  -\n"
+        );
+    }
+
+    #[test]
+    fn test_failing() {
+        let (names, mut results) = results();
+        assert_eq!(failing(&names, &results), vec!["alice"]);
+        results[0].as_mut().unwrap()[0] = String::from("TIMEOUT");
+        assert!(failing(&names, &results).is_empty());
+    }
+}
@@ -0,0 +1,300 @@
+use crate::{answers, check_params, get_year, not_found, profile, Options};
+use std::path::Path;
+use std::time::Duration;
+
+// Implementation status of the years: the stars solved per day, whether
+// the answers match the ones stored in input/yearYYYY/dayDD.answers, and
+// the run times. Days are only run if their input is there.
+
+const LEGEND: &str = "** both stars, * star 1 only, - stub, \
+                      ✓ matches the stored answers, ✗ differs";
+
+#[derive(Debug, Default)]
+pub struct DayStatus {
+    pub day_no: usize,
+    pub stars: usize,
+    // None if not run or no answers stored
+    pub verified: Option<bool>,
+    // None if not run
+    pub time: Option<Duration>,
+}
+
+#[derive(Debug, Default)]
+pub struct YearStatus {
+    pub year_no: usize,
+    pub days: Vec<DayStatus>,
+}
+
+impl YearStatus {
+    fn day(&self, day_no: usize) -> Option<&DayStatus> {
+        self.days.iter().find(|d| d.day_no == day_no)
+    }
+
+    fn stars(&self) -> usize {
+        self.days.iter().map(|d| d.stars).sum()
+    }
+
+    fn total_time(&self) -> Option<Duration> {
+        self.days.iter().filter_map(|d| d.time).reduce(|a, b| a + b)
+    }
+
+    fn slowest(&self, n: usize) -> Vec<&DayStatus> {
+        let mut days: Vec<_> = self.days.iter().filter(|d| d.time.is_some()).collect();
+        days.sort_by_key(|d| std::cmp::Reverse(d.time));
+        days.truncate(n);
+        days
+    }
+}
+
+// status [year] [--markdown]
+pub fn status_cmd(args: &[String], options: &Options) {
+    let years = match args {
+        [] => solutions::YEARS.to_vec(),
+        [year] => match year.parse::<usize>() {
+            Ok(year_no) => vec![year_no],
+            Err(_) => return println!("Usage: status [year] [--markdown]"),
+        },
+        _ => return println!("Usage: status [year] [--markdown]"),
+    };
+    if !options.markdown {
+        println!("{}\n", LEGEND);
+    }
+    for year_no in years {
+        if let Err(e) = get_year(year_no) {
+            println!("{}", not_found(year_no, 0, e));
+            continue;
+        }
+        if let Err(e) = check_params(&options.params, year_no, 1..=days_in(year_no)) {
+            println!("Error: {}", e);
+            continue;
+        }
+        let status = year_status(year_no, options);
+        if options.markdown {
+            println!("{}", format_markdown(&status));
+        } else {
+            println!("{}", format_grid(&status));
+        }
+    }
+}
+
+// "star1: ..." and "star2: ..." lines, as in the fixtures.
+pub fn parse_answers(s: &str) -> [Option<String>; 2] {
+    let mut answers = [None, None];
+    for (key, value) in s.lines().filter_map(|line| line.split_once(':')) {
+        match key.trim() {
+            "star1" => answers[0] = Some(value.trim().to_string()),
+            "star2" => answers[1] = Some(value.trim().to_string()),
+            _ => {}
+        }
+    }
+    answers
+}
+
+pub fn format_answers(answers: &[String]) -> String {
+    answers
+        .iter()
+        .enumerate()
+        .map(|(i, answer)| format!("star{}: {}\n", i + 1, answer))
+        .collect()
+}
+
+fn year_status(year_no: usize, options: &Options) -> YearStatus {
+    let year = get_year(year_no).unwrap();
+    let mut status = YearStatus {
+        year_no,
+        ..Default::default()
+    };
+    for day_no in 1..=days_in(year_no) {
+        let Some(day) = year.get_day(day_no) else {
+            continue;
+        };
+        let mut day_status = DayStatus {
+            day_no,
+            stars: day.stars(),
+            ..Default::default()
+        };
+        if day_status.stars > 0 {
+            eprint!("\rrunning {} day {:02}", year_no, day_no);
+            let profile = options.profile();
+            if let Ok(input) =
+                std::fs::read_to_string(profile::path(profile, year_no, day_no, "input"))
+            {
+                let (results, time) = answers(year_no, day_no, input, options);
+                // skipped after a worker ignored its timeout: not run
+                if !results.iter().any(|res| res == "SKIPPED") {
+                    day_status.stars = solved(day_status.stars, &results);
+                    day_status.time = Some(time);
+                    let stored = profile::path(profile, year_no, day_no, "answers");
+                    day_status.verified = verify(&stored, day_status.stars, &results);
+                }
+            }
+            eprint!("\r                    \r");
+        }
+        status.days.push(day_status);
+    }
+    status
+}
+
+// A star answering "not implemented" isn't solved, whatever stars() says.
+fn solved(stars: usize, results: &[String]) -> usize {
+    results
+        .iter()
+        .take(stars)
+        .position(|res| res == "not implemented")
+        .unwrap_or(stars)
+}
+
+fn verify(stored: &Path, stars: usize, results: &[String]) -> Option<bool> {
+    let stored = parse_answers(&std::fs::read_to_string(stored).ok()?);
+    let checks: Vec<_> = stored
+        .iter()
+        .zip(results)
+        .take(stars)
+        .filter_map(|(answer, res)| answer.as_ref().map(|a| a == res))
+        .collect();
+    if checks.is_empty() {
+        None
+    } else {
+        Some(checks.iter().all(|ok| *ok))
+    }
+}
+
+// Since 2025 there are only 12 puzzles.
+fn days_in(year_no: usize) -> usize {
+    if year_no >= 2025 {
+        12
+    } else {
+        25
+    }
+}
+
+fn marker(day: Option<&DayStatus>, star: &str) -> String {
+    let Some(day) = day else {
+        return String::new();
+    };
+    let stars = if day.stars == 0 {
+        String::from("-")
+    } else {
+        star.repeat(day.stars)
+    };
+    let check = match day.verified {
+        Some(true) => "✓",
+        Some(false) => "✗",
+        None => "",
+    };
+    stars + check
+}
+
+fn summary(status: &YearStatus) -> String {
+    let mut summary = format!("{} stars", status.stars());
+    if let Some(time) = status.total_time() {
+        summary += &format!(", total time: {} ms", time.as_millis());
+    }
+    let slowest: Vec<_> = status
+        .slowest(3)
+        .iter()
+        .map(|d| format!("day {} ({} ms)", d.day_no, d.time.unwrap().as_millis()))
+        .collect();
+    if !slowest.is_empty() {
+        summary += &format!(", slowest: {}", slowest.join(", "));
+    }
+    summary
+}
+
+pub fn format_grid(status: &YearStatus) -> String {
+    let mut grid = format!("{}  {}\n", status.year_no, summary(status));
+    let days: Vec<_> = (1..=days_in(status.year_no)).collect();
+    for row in days.chunks(5) {
+        let cells: Vec<_> = row
+            .iter()
+            .map(|&d| format!("{:>2} {:<3}", d, marker(status.day(d), "*")))
+            .collect();
+        grid += cells.join("  ").trim_end();
+        grid.push('\n');
+    }
+    grid
+}
+
+pub fn format_markdown(status: &YearStatus) -> String {
+    let mut md = format!("### {}\n\n|   |   |   |   |   |\n", status.year_no);
+    md += "|---|---|---|---|---|\n";
+    let days: Vec<_> = (1..=days_in(status.year_no)).collect();
+    for row in days.chunks(5) {
+        let cells: Vec<_> = row
+            .iter()
+            .map(|&d| format!(" {} {} ", d, marker(status.day(d), "★")).replace("  ", " "))
+            .collect();
+        md += &format!("|{}|\n", cells.join("|"));
+    }
+    md + &format!("\n{}\n", summary(status))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn day(day_no: usize, stars: usize, verified: Option<bool>, ms: Option<u64>) -> DayStatus {
+        DayStatus {
+            day_no,
+            stars,
+            verified,
+            time: ms.map(Duration::from_millis),
+        }
+    }
+
+    fn example() -> YearStatus {
+        YearStatus {
+            year_no: 2025,
+            days: vec![
+                day(1, 2, Some(true), Some(3)),
+                day(2, 2, Some(false), Some(40)),
+                day(3, 2, None, Some(10)),
+                day(4, 1, None, None),
+                day(5, 0, None, None),
+            ],
+        }
+    }
+
+    #[test]
+    fn test_grid() {
+        assert_eq!(
+            format_grid(&example()),
+            "2025  7 stars, total time: 53 ms, slowest: day 2 (40 ms), day 3 (10 ms), day 1 (3 ms)\n\
+             \x201 **✓   2 **✗   3 **    4 *     5 -\n\
+             \x206       7       8       9      10\n\
+             11      12\n"
+        );
+    }
+
+    #[test]
+    fn test_markdown() {
+        let md = format_markdown(&example());
+        assert!(md.starts_with("### 2025\n\n"));
+        assert!(md.contains("\n| 1 ★★✓ | 2 ★★✗ | 3 ★★ | 4 ★ | 5 - |\n"));
+        assert!(md.contains("\n| 11 | 12 |\n"));
+        assert!(md.ends_with(
+            "\n7 stars, total time: 53 ms, slowest: day 2 (40 ms), day 3 (10 ms), day 1 (3 ms)\n"
+        ));
+    }
+
+    #[test]
+    fn test_solved() {
+        let results = vec![String::from("22"), String::from("not implemented")];
+        assert_eq!(solved(2, &results), 1);
+        assert_eq!(solved(1, &results), 1);
+        assert_eq!(solved(0, &results), 0);
+        assert_eq!(solved(2, &[String::from("22"), String::from("6,1")]), 2);
+    }
+
+    #[test]
+    fn test_answers() {
+        let answers = vec![String::from("22"), String::from("6,1")];
+        let s = format_answers(&answers);
+        assert_eq!(s, "star1: 22\nstar2: 6,1\n");
+        assert_eq!(
+            parse_answers(&s),
+            [Some(answers[0].clone()), Some(answers[1].clone())]
+        );
+        assert_eq!(parse_answers("star1: 7\n"), [Some(String::from("7")), None]);
+    }
+}
@@ -0,0 +1,269 @@
+use crate::{not_found, profile, Options};
+use common::fixture;
+use common::params::Params;
+use std::collections::HashMap;
+use std::panic::{self, AssertUnwindSafe};
+use std::path::{Path, PathBuf};
+use std::thread;
+use std::time::{Duration, Instant, SystemTime};
+
+// Re-runs a day whenever its input or its example fixtures change and
+// compares the answers with the previous run. The answers of the last run
+// are kept in input/yearYYYY/dayDD.last, so the watcher can be restarted
+// at any time, e.g. by cargo watch after a rebuild.
+
+const POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct Answer {
+    // "input" or the fixture name
+    pub source: String,
+    pub star_no: usize,
+    pub value: String,
+    pub expected: Option<String>,
+    pub time: Duration,
+}
+
+impl Answer {
+    fn key(&self) -> String {
+        format!("{}::star{}", self.source, self.star_no)
+    }
+}
+
+type Stamps = Vec<(PathBuf, Option<SystemTime>)>;
+
+pub fn watch(year_no: usize, day_no: usize, options: &Options) {
+    if let Err(e) = solutions::get_day(year_no, day_no) {
+        return println!("{}", not_found(year_no, day_no, e));
+    }
+    let profile = options.profile();
+    let state_path = profile::path(profile, year_no, day_no, "last");
+    let mut previous = std::fs::read_to_string(&state_path)
+        .map(|s| parse_state(&s))
+        .unwrap_or_default();
+    let mut last_stamps = None;
+    loop {
+        let stamps = stamps(profile, year_no, day_no);
+        if last_stamps.as_ref() != Some(&stamps) {
+            last_stamps = Some(stamps);
+            println!("Year: {:04}, Day: {:02}", year_no, day_no);
+            let answers = solve(profile, year_no, day_no, &options.params);
+            for answer in &answers {
+                println!("{}", format_answer(answer, previous.get(&answer.key())));
+            }
+            previous = answers.iter().map(|a| (a.key(), a.value.clone())).collect();
+            if let Some(dir) = state_path.parent() {
+                std::fs::create_dir_all(dir).unwrap();
+            }
+            std::fs::write(&state_path, format_state(&answers)).unwrap();
+            println!("Watching for changes...\n");
+        }
+        thread::sleep(POLL_INTERVAL);
+    }
+}
+
+// Modification times of the input and the fixtures; a file missing has
+// none, a file removed drops out.
+fn stamps(profile: Option<&str>, year_no: usize, day_no: usize) -> Stamps {
+    let mtime = |path: &Path| path.metadata().and_then(|m| m.modified()).ok();
+    let input = profile::path(profile, year_no, day_no, "input");
+    let mut stamps = vec![(input.clone(), mtime(&input))];
+    if let Ok(entries) = std::fs::read_dir(fixture::dir(year_no, day_no)) {
+        let mut fixtures: Stamps = entries
+            .filter_map(|e| e.ok())
+            .map(|e| e.path())
+            .filter(|p| p.extension().is_some_and(|ext| ext == "txt"))
+            .map(|p| {
+                let time = mtime(&p);
+                (p, time)
+            })
+            .collect();
+        fixtures.sort();
+        stamps.extend(fixtures);
+    }
+    stamps
+}
+
+fn solve(profile: Option<&str>, year_no: usize, day_no: usize, params: &Params) -> Vec<Answer> {
+    let mut answers = vec![];
+    let path = profile::path(profile, year_no, day_no, "input");
+    match std::fs::read_to_string(&path) {
+        Ok(input) => {
+            let results = solve_stars(year_no, day_no, &input, params, &[1, 2]);
+            for (star_no, (value, time)) in (1..).zip(results) {
+                answers.push(Answer {
+                    source: String::from("input"),
+                    star_no,
+                    value,
+                    expected: None,
+                    time,
+                });
+            }
+        }
+        Err(_) => println!("No input: {}", path.display()),
+    }
+    let fixtures = match fixture::load(year_no, day_no) {
+        Ok(fixtures) => fixtures,
+        Err(e) => {
+            println!("Invalid fixture {}", e);
+            vec![]
+        }
+    };
+    let declared = solutions::get_day(year_no, day_no).unwrap().params();
+    for fixture in fixtures {
+        if let Err(e) = fixture.params.check(&declared) {
+            println!("Invalid fixture {}: {}", fixture.name, e);
+            continue;
+        }
+        let expected: Vec<_> = [(1, &fixture.star1), (2, &fixture.star2)]
+            .into_iter()
+            .filter_map(|(star_no, expected)| expected.clone().map(|e| (star_no, e)))
+            .collect();
+        let star_nos: Vec<_> = expected.iter().map(|(star_no, _)| *star_no).collect();
+        let results = solve_stars(year_no, day_no, &fixture.input, &fixture.params, &star_nos);
+        for ((star_no, expected), (value, time)) in expected.into_iter().zip(results) {
+            answers.push(Answer {
+                source: fixture.name.clone(),
+                star_no,
+                value,
+                expected: Some(expected),
+                time,
+            });
+        }
+    }
+    answers
+}
+
+// The input is parsed once for all stars. A panic (reported by the panic
+// hook) must not end the watcher, nor keep the other star from running.
+fn solve_stars(
+    year_no: usize,
+    day_no: usize,
+    input: &str,
+    params: &Params,
+    star_nos: &[usize],
+) -> Vec<(String, Duration)> {
+    let day = solutions::get_day(year_no, day_no).unwrap();
+    let failed = |res: String| {
+        star_nos
+            .iter()
+            .map(|_| (res.clone(), Duration::ZERO))
+            .collect()
+    };
+    let prepared = match panic::catch_unwind(AssertUnwindSafe(|| day.prepare(input))) {
+        Ok(Ok(prepared)) => prepared,
+        Ok(Err(e)) => return failed(format!("parse error: {}", e)),
+        Err(_) => return failed(String::from("PANIC")),
+    };
+    star_nos
+        .iter()
+        .map(|&star_no| {
+            let start = Instant::now();
+            let res = panic::catch_unwind(AssertUnwindSafe(|| {
+                prepared.solve(day.as_ref(), star_no, params)
+            }));
+            (
+                res.unwrap_or_else(|_| String::from("PANIC")),
+                start.elapsed(),
+            )
+        })
+        .collect()
+}
+
+pub fn format_answer(answer: &Answer, previous: Option<&String>) -> String {
+    let res = if answer.value.contains('\n') {
+        format!("\n{}", answer.value)
+    } else {
+        answer.value.clone()
+    };
+    let check = match &answer.expected {
+        Some(expected) if *expected == answer.value => String::from("    ok"),
+        Some(expected) => format!("    FAILED, expected {}", expected),
+        None => String::new(),
+    };
+    let change = match previous {
+        None => String::from("    (new)"),
+        Some(previous) if *previous != answer.value => {
+            format!("    (was {})", escape(previous))
+        }
+        Some(_) => String::new(),
+    };
+    format!(
+        "{:<8} star {}  time: {:>10} µs    res: {}{}{}",
+        answer.source,
+        answer.star_no,
+        answer.time.as_micros(),
+        res,
+        check,
+        change
+    )
+}
+
+// One "source::starN: value" line per answer, with newlines escaped for
+// answers drawn as letters.
+pub fn format_state(answers: &[Answer]) -> String {
+    answers
+        .iter()
+        .map(|a| format!("{}: {}\n", a.key(), escape(&a.value)))
+        .collect()
+}
+
+pub fn parse_state(s: &str) -> HashMap<String, String> {
+    s.lines()
+        .filter_map(|line| line.split_once(": "))
+        .map(|(key, value)| (key.to_string(), value.replace("\\n", "\n")))
+        .collect()
+}
+
+fn escape(value: &str) -> String {
+    value.replace('\n', "\\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn answer(source: &str, star_no: usize, value: &str, expected: Option<&str>) -> Answer {
+        Answer {
+            source: source.to_string(),
+            star_no,
+            value: value.to_string(),
+            expected: expected.map(|s| s.to_string()),
+            time: Duration::from_micros(42),
+        }
+    }
+
+    #[test]
+    fn test_state() {
+        let answers = vec![
+            answer("input", 1, "22", None),
+            answer("ex1", 2, "#.\n.#", None),
+        ];
+        let state = format_state(&answers);
+        assert_eq!(state, "input::star1: 22\nex1::star2: #.\\n.#\n");
+        let parsed = parse_state(&state);
+        assert_eq!(parsed.len(), 2);
+        assert_eq!(parsed["input::star1"], "22");
+        assert_eq!(parsed["ex1::star2"], "#.\n.#");
+    }
+
+    #[test]
+    fn test_format_answer() {
+        let prev = String::from("21");
+        assert_eq!(
+            format_answer(&answer("input", 1, "22", None), Some(&prev)),
+            "input    star 1  time:         42 µs    res: 22    (was 21)"
+        );
+        assert_eq!(
+            format_answer(
+                &answer("ex1", 1, "22", Some("22")),
+                Some(&String::from("22"))
+            ),
+            "ex1      star 1  time:         42 µs    res: 22    ok"
+        );
+        assert_eq!(
+            format_answer(&answer("ex1", 2, "6,2", Some("6,1")), None),
+            "ex1      star 2  time:         42 µs    res: 6,2    FAILED, expected 6,1    (new)"
+        );
+    }
+}
@@ -1,4 +1,179 @@
+use crate::params::{ParamInfo, Params};
+use std::fmt;
+
 pub trait Day {
     fn star1(&self, input: &str) -> String;
     fn star2(&self, input: &str) -> String;
+
+    // Days with puzzle parameters override these, star1/star2 then call
+    // them with the defaults for the real input.
+    fn star1_with(&self, input: &str, _params: &Params) -> String {
+        self.star1(input)
+    }
+
+    fn star2_with(&self, input: &str, _params: &Params) -> String {
+        self.star2(input)
+    }
+
+    // The puzzle parameters star1_with/star2_with look at.
+    fn params(&self) -> Vec<ParamInfo> {
+        vec![]
+    }
+
+    // Stars solved: none for a new day from the template, one for day 25.
+    fn stars(&self) -> usize {
+        2
+    }
+
+    // Days implementing ParsedDay parse here once for both stars, all
+    // others get the raw input passed to each star.
+    fn prepare<'a>(&'a self, input: &'a str) -> Result<Prepared<'a>, String> {
+        Ok(Prepared::Raw(input))
+    }
+}
+
+// Days with a separate parsing step. Implementing this gives a Day.
+pub trait ParsedDay {
+    type Parsed;
+    type Error: fmt::Display;
+
+    fn parse(&self, input: &str) -> Result<Self::Parsed, Self::Error>;
+    fn part1(&self, parsed: &Self::Parsed) -> String;
+    fn part2(&self, parsed: &Self::Parsed) -> String;
+
+    fn part1_with(&self, parsed: &Self::Parsed, _params: &Params) -> String {
+        self.part1(parsed)
+    }
+
+    fn part2_with(&self, parsed: &Self::Parsed, _params: &Params) -> String {
+        self.part2(parsed)
+    }
+
+    // as Day::params and Day::stars
+    fn params(&self) -> Vec<ParamInfo> {
+        vec![]
+    }
+
+    fn stars(&self) -> usize {
+        2
+    }
+}
+
+pub trait Solve {
+    fn star1(&self, params: &Params) -> String;
+    fn star2(&self, params: &Params) -> String;
+}
+
+pub enum Prepared<'a> {
+    Raw(&'a str),
+    Parsed(Box<dyn Solve + 'a>),
+}
+
+impl Prepared<'_> {
+    // Solves a star of the day which prepared the input, without parsing
+    // it again.
+    pub fn solve(&self, day: &dyn Day, star_no: usize, params: &Params) -> String {
+        match (self, star_no) {
+            (Prepared::Raw(input), 1) => day.star1_with(input, params),
+            (Prepared::Raw(input), 2) => day.star2_with(input, params),
+            (Prepared::Parsed(solve), 1) => solve.star1(params),
+            (Prepared::Parsed(solve), 2) => solve.star2(params),
+            _ => format!("invalid star_no: {}", star_no),
+        }
+    }
+}
+
+struct WithParsed<'a, D: ParsedDay> {
+    day: &'a D,
+    parsed: D::Parsed,
+}
+
+impl<D: ParsedDay> Solve for WithParsed<'_, D> {
+    fn star1(&self, params: &Params) -> String {
+        self.day.part1_with(&self.parsed, params)
+    }
+
+    fn star2(&self, params: &Params) -> String {
+        self.day.part2_with(&self.parsed, params)
+    }
+}
+
+impl<D: ParsedDay> Day for D {
+    fn star1(&self, input: &str) -> String {
+        self.star1_with(input, &Params::default())
+    }
+
+    fn star2(&self, input: &str) -> String {
+        self.star2_with(input, &Params::default())
+    }
+
+    fn star1_with(&self, input: &str, params: &Params) -> String {
+        match self.parse(input) {
+            Ok(parsed) => self.part1_with(&parsed, params),
+            Err(e) => format!("parse error: {}", e),
+        }
+    }
+
+    fn star2_with(&self, input: &str, params: &Params) -> String {
+        match self.parse(input) {
+            Ok(parsed) => self.part2_with(&parsed, params),
+            Err(e) => format!("parse error: {}", e),
+        }
+    }
+
+    fn params(&self) -> Vec<ParamInfo> {
+        ParsedDay::params(self)
+    }
+
+    fn stars(&self) -> usize {
+        ParsedDay::stars(self)
+    }
+
+    fn prepare<'a>(&'a self, input: &'a str) -> Result<Prepared<'a>, String> {
+        let parsed = self.parse(input).map_err(|e| e.to_string())?;
+        Ok(Prepared::Parsed(Box::new(WithParsed { day: self, parsed })))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct Sum {}
+
+    impl ParsedDay for Sum {
+        type Parsed = Vec<i64>;
+        type Error = std::num::ParseIntError;
+
+        fn parse(&self, input: &str) -> Result<Vec<i64>, Self::Error> {
+            input.lines().map(|l| l.parse()).collect()
+        }
+
+        fn part1(&self, parsed: &Vec<i64>) -> String {
+            parsed.iter().sum::<i64>().to_string()
+        }
+
+        fn part2(&self, parsed: &Vec<i64>) -> String {
+            parsed.iter().product::<i64>().to_string()
+        }
+
+        fn stars(&self) -> usize {
+            1
+        }
+    }
+
+    #[test]
+    fn test_adapter() {
+        let day: Box<dyn Day> = Box::new(Sum {});
+        assert_eq!(day.star1("2\n3"), "5");
+        assert_eq!(day.star2("2\n3"), "6");
+        assert!(day.star1("x").starts_with("parse error"));
+        assert_eq!(day.stars(), 1);
+
+        let prepared = day.prepare("2\n3\n4").unwrap();
+        assert!(matches!(prepared, Prepared::Parsed(_)));
+        assert_eq!(prepared.solve(day.as_ref(), 1, &Params::default()), "9");
+        assert_eq!(prepared.solve(day.as_ref(), 2, &Params::default()), "24");
+        assert!(day.prepare("x").is_err());
+    }
 }
@@ -0,0 +1,206 @@
+use crate::params::Params;
+use std::fmt;
+use std::path::{Path, PathBuf};
+
+// Example inputs with their expected answers, stored as
+// examples/yearYYYY/dayDD/<name>.txt:
+//
+//     star1: 26
+//     star2: 56000011
+//     param: row=10
+//     ---
+//     <example input>
+//
+// All header lines are optional; everything after the "---" line is the
+// input, unchanged.
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Fixture {
+    pub name: String,
+    pub star1: Option<String>,
+    pub star2: Option<String>,
+    pub params: Params,
+    // "key=value" as given, to write them back unchanged
+    param_lines: Vec<String>,
+    pub input: String,
+}
+
+impl Fixture {
+    pub fn new(name: &str, input: &str) -> Fixture {
+        Fixture {
+            name: name.to_string(),
+            input: input.to_string(),
+            ..Default::default()
+        }
+    }
+
+    pub fn with_param(mut self, assignment: &str) -> Result<Fixture, String> {
+        self.params.set(assignment)?;
+        self.param_lines.push(assignment.to_string());
+        Ok(self)
+    }
+
+    pub fn parse(name: &str, text: &str) -> Result<Fixture, String> {
+        let (header, input) = if let Some(input) = text.strip_prefix("---\n") {
+            ("", input)
+        } else {
+            text.split_once("\n---\n")
+                .ok_or_else(|| format!("{}: missing '---' line", name))?
+        };
+        let mut fixture = Fixture::new(name, input);
+        for line in header.lines().filter(|l| !l.trim().is_empty()) {
+            let (key, value) = line
+                .split_once(':')
+                .ok_or_else(|| format!("{}: invalid header line: {}", name, line))?;
+            let value = value.trim();
+            match key.trim() {
+                "star1" => fixture.star1 = Some(value.to_string()),
+                "star2" => fixture.star2 = Some(value.to_string()),
+                "param" => {
+                    fixture = fixture
+                        .with_param(value)
+                        .map_err(|e| format!("{}: {}", name, e))?
+                }
+                _ => return Err(format!("{}: unknown header: {}", name, key)),
+            }
+        }
+        Ok(fixture)
+    }
+}
+
+impl fmt::Display for Fixture {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Some(star1) = &self.star1 {
+            writeln!(f, "star1: {}", star1)?;
+        }
+        if let Some(star2) = &self.star2 {
+            writeln!(f, "star2: {}", star2)?;
+        }
+        for param in &self.param_lines {
+            writeln!(f, "param: {}", param)?;
+        }
+        writeln!(f, "---")?;
+        write!(f, "{}", self.input)
+    }
+}
+
+// The repository root as built from, so the tests of the year crates and
+// the runner, wherever it's started, read and write the same fixtures.
+pub fn root() -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR"))
+        .parent()
+        .unwrap()
+        .to_path_buf()
+}
+
+pub fn dir(year_no: usize, day_no: usize) -> PathBuf {
+    root()
+        .join("examples")
+        .join(format!("year{:04}", year_no))
+        .join(format!("day{:02}", day_no))
+}
+
+// All fixtures of a day, sorted by name. A missing directory means no
+// fixtures.
+pub fn load(year_no: usize, day_no: usize) -> Result<Vec<Fixture>, String> {
+    let entries = match std::fs::read_dir(dir(year_no, day_no)) {
+        Ok(entries) => entries,
+        Err(_) => return Ok(vec![]),
+    };
+    let mut paths: Vec<_> = entries
+        .filter_map(|e| e.ok())
+        .map(|e| e.path())
+        .filter(|p| p.extension().is_some_and(|ext| ext == "txt"))
+        .collect();
+    paths.sort();
+    paths
+        .iter()
+        .map(|path| {
+            let name = path.file_stem().unwrap().to_string_lossy();
+            let text = std::fs::read_to_string(path).map_err(|e| format!("{}: {}", name, e))?;
+            Fixture::parse(&name, &text)
+        })
+        .collect()
+}
+
+// Test harness for the year crates, with one test per fixture checking
+// its stars on a single parse, e.g. "day18::ex1". In tests/examples.rs
+// (with harness = false):
+//
+//     fn main() -> ExitCode {
+//         common::fixture::harness(2024, |day_no| Year2024 {}.get_day(day_no))
+//     }
+#[cfg(feature = "harness")]
+pub fn harness(
+    year_no: usize,
+    get_day: fn(usize) -> Option<Box<dyn crate::day::Day>>,
+) -> std::process::ExitCode {
+    use libtest_mimic::{Arguments, Failed, Trial};
+
+    let mut trials = vec![];
+    for day_no in (1..=25).filter(|d| get_day(*d).is_some()) {
+        let fixtures = match load(year_no, day_no) {
+            Ok(fixtures) => fixtures,
+            Err(e) => {
+                let name = format!("day{:02}::fixtures", day_no);
+                trials.push(Trial::test(name, move || Err(Failed::from(e))));
+                continue;
+            }
+        };
+        for fixture in fixtures {
+            let name = format!("day{:02}::{}", day_no, fixture.name);
+            trials.push(Trial::test(name, move || {
+                let day = get_day(day_no).unwrap();
+                fixture.params.check(&day.params()).map_err(Failed::from)?;
+                let prepared = day.prepare(&fixture.input).map_err(Failed::from)?;
+                let mut failed = vec![];
+                for (star_no, expected) in [(1, &fixture.star1), (2, &fixture.star2)] {
+                    let Some(expected) = expected else {
+                        continue;
+                    };
+                    let actual = prepared.solve(day.as_ref(), star_no, &fixture.params);
+                    if actual != *expected {
+                        failed.push(format!(
+                            "star{}: expected {}, got {}",
+                            star_no, expected, actual
+                        ));
+                    }
+                }
+                if failed.is_empty() {
+                    Ok(())
+                } else {
+                    Err(Failed::from(failed.join("\n")))
+                }
+            }));
+        }
+    }
+    libtest_mimic::run(&Arguments::from_args(), trials).exit_code()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse() {
+        let text = "star1: 26\nparam: row=10\n---\n1 2\n3 4\n";
+        let fixture = Fixture::parse("ex1", text).unwrap();
+        assert_eq!(fixture.star1.as_deref(), Some("26"));
+        assert_eq!(fixture.star2, None);
+        assert_eq!(fixture.params, Params::from([("row", "10")]));
+        assert_eq!(fixture.input, "1 2\n3 4\n");
+        assert_eq!(fixture.to_string(), text);
+
+        let fixture = Fixture::parse("ex2", "---\n---\n").unwrap();
+        assert_eq!(fixture.input, "---\n");
+        assert!(Fixture::parse("ex3", "star1: 1\n").is_err());
+        assert!(Fixture::parse("ex4", "answer: 1\n---\n").is_err());
+    }
+
+    #[test]
+    fn test_dir() {
+        assert!(root().join("Cargo.toml").exists());
+        assert_eq!(dir(2024, 8), root().join("examples/year2024/day08"));
+        assert_eq!(load(1999, 1), Ok(vec![]));
+    }
+}
@@ -1,2 +1,4 @@
 pub mod day;
+pub mod fixture;
+pub mod params;
 pub mod year;
@@ -0,0 +1,134 @@
+use std::collections::HashMap;
+use std::str::FromStr;
+
+// Puzzle parameters, like the row to look at or the size of the grid,
+// which differ between the examples and the real input. Days declare each
+// as a Param with the default for the real input and list them in
+// Day::params; overrides come from tests, fixtures or the --param
+// key=value flag, and are checked against the declarations before the day
+// runs.
+
+pub struct Param<T> {
+    pub key: &'static str,
+    pub default: T,
+}
+
+impl<T: FromStr> Param<T> {
+    pub const fn new(key: &'static str, default: T) -> Param<T> {
+        Param { key, default }
+    }
+
+    pub fn info(&self) -> ParamInfo {
+        ParamInfo {
+            key: self.key,
+            valid: |value| value.parse::<T>().is_ok(),
+        }
+    }
+}
+
+// A declared parameter without its type, to check overrides against.
+#[derive(Clone, Copy)]
+pub struct ParamInfo {
+    pub key: &'static str,
+    valid: fn(&str) -> bool,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Params {
+    values: HashMap<String, String>,
+}
+
+impl Params {
+    pub fn new() -> Params {
+        Params::default()
+    }
+
+    pub fn with(mut self, key: &str, value: impl ToString) -> Params {
+        self.values.insert(key.to_string(), value.to_string());
+        self
+    }
+
+    // Parses "key=value".
+    pub fn set(&mut self, assignment: &str) -> Result<(), String> {
+        match assignment.split_once('=') {
+            Some((key, value)) if !key.is_empty() => {
+                self.values.insert(key.to_string(), value.to_string());
+                Ok(())
+            }
+            _ => Err(format!("invalid parameter: {}", assignment)),
+        }
+    }
+
+    // Every key must be declared, and its value valid for each declaration
+    // (the days of a year may share a key).
+    pub fn check(&self, declared: &[ParamInfo]) -> Result<(), String> {
+        let mut keys: Vec<_> = self.values.keys().collect();
+        keys.sort();
+        for key in keys {
+            let value = &self.values[key];
+            let infos: Vec<_> = declared.iter().filter(|p| p.key == key).collect();
+            if infos.is_empty() {
+                return Err(format!("unknown parameter: {}", key));
+            }
+            if infos.iter().any(|p| !(p.valid)(value)) {
+                return Err(format!("invalid value for parameter {}: {}", key, value));
+            }
+        }
+        Ok(())
+    }
+
+    pub fn get<T: FromStr + Clone>(&self, param: &Param<T>) -> T {
+        match self.values.get(param.key) {
+            Some(value) => value
+                .parse()
+                .unwrap_or_else(|_| panic!("Invalid value for parameter {}: {}", param.key, value)),
+            None => param.default.clone(),
+        }
+    }
+}
+
+impl<const N: usize> From<[(&str, &str); N]> for Params {
+    fn from(values: [(&str, &str); N]) -> Params {
+        values
+            .into_iter()
+            .fold(Params::new(), |params, (k, v)| params.with(k, v))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ROW: Param<i64> = Param::new("row", 2000000);
+    const SIZE: Param<usize> = Param::new("size", 71);
+
+    #[test]
+    fn test_params() {
+        let mut params = Params::from([("row", "10")]);
+        assert_eq!(params.get(&ROW), 10);
+        assert_eq!(params.get(&SIZE), 71);
+
+        params.set("size=7").unwrap();
+        assert_eq!(params.get(&SIZE), 7);
+        assert_eq!(params, Params::new().with("row", 10).with("size", 7));
+        assert!(params.set("size").is_err());
+        assert!(params.set("=3").is_err());
+    }
+
+    #[test]
+    fn test_check() {
+        let declared = [ROW.info(), SIZE.info()];
+        assert!(Params::from([("row", "-10"), ("size", "7")])
+            .check(&declared)
+            .is_ok());
+        assert_eq!(
+            Params::from([("rows", "10")]).check(&declared),
+            Err(String::from("unknown parameter: rows"))
+        );
+        assert_eq!(
+            Params::from([("size", "-7")]).check(&declared),
+            Err(String::from("invalid value for parameter size: -7"))
+        );
+        assert!(Params::new().check(&[]).is_ok());
+    }
+}
@@ -0,0 +1,250 @@
+use common::day::Day;
+use common::params::Params;
+use common::year::Year;
+use std::fmt;
+use std::path::Path;
+
+// Registry of all years and days, to use the solutions without the
+// runner, e.g. from other tools, tests or benchmarks:
+//
+//     let answer = solutions::solve(2024, 1, 1, &input)?;
+
+// Each year is listed once here, with the cargo feature compiling it in;
+// get_year and YEAR_MAX follow from it.
+macro_rules! years {
+    ($($year_no:literal: $feature:literal => $krate:ident::$year:ident),* $(,)?) => {
+        // also the years not compiled in
+        pub const ALL_YEARS: &[usize] = &[$($year_no),*];
+
+        pub const YEARS: &[usize] = &[$(#[cfg(feature = $feature)] $year_no),*];
+
+        pub fn get_year(year_no: usize) -> Result<Box<dyn Year>, Error> {
+            match year_no {
+                $(#[cfg(feature = $feature)] $year_no => Ok(Box::new($krate::$year {})),)*
+                _ => Err(missing(year_no)),
+            }
+        }
+    };
+}
+
+years! {
+    2015: "year2015" => year2015::Year2015,
+    2016: "year2016" => year2016::Year2016,
+    2017: "year2017" => year2017::Year2017,
+    2018: "year2018" => year2018::Year2018,
+    2019: "year2019" => year2019::Year2019,
+    2020: "year2020" => year2020::Year2020,
+    2021: "year2021" => year2021::Year2021,
+    2022: "year2022" => year2022::Year2022,
+    2023: "year2023" => year2023::Year2023,
+    2024: "year2024" => year2024::Year2024,
+    2025: "year2025" => year2025::Year2025,
+}
+
+// Why get_year has no such year.
+fn missing(year_no: usize) -> Error {
+    if ALL_YEARS.contains(&year_no) {
+        Error::NotCompiledIn
+    } else {
+        Error::NoSuchYear
+    }
+}
+
+// The most recent year compiled in, 0 if none.
+pub const YEAR_MAX: usize = {
+    let mut year_max = 0;
+    let mut i = 0;
+    while i < YEARS.len() {
+        if YEARS[i] > year_max {
+            year_max = YEARS[i];
+        }
+        i += 1;
+    }
+    year_max
+};
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DayInfo {
+    pub year_no: usize,
+    pub day_no: usize,
+    // only set by days_with_titles
+    pub title: Option<String>,
+    pub stars: usize,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Error {
+    NoSuchYear,
+    NotCompiledIn,
+    NoSuchDay,
+    NoSuchStar,
+    NotImplemented,
+    InvalidParams,
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let msg = match self {
+            Error::NoSuchYear => "year not implemented",
+            Error::NotCompiledIn => "year not compiled in",
+            Error::NoSuchDay => "day not implemented",
+            Error::NoSuchStar => "invalid star",
+            Error::NotImplemented => "star not implemented",
+            Error::InvalidParams => "unknown parameter or invalid value",
+        };
+        write!(f, "{}", msg)
+    }
+}
+
+pub type Answer = Result<String, Error>;
+
+pub fn get_day(year_no: usize, day_no: usize) -> Result<Box<dyn Day>, Error> {
+    get_year(year_no)?.get_day(day_no).ok_or(Error::NoSuchDay)
+}
+
+// All implemented days of a year.
+pub fn days(year_no: usize) -> Vec<DayInfo> {
+    let Ok(year) = get_year(year_no) else {
+        return vec![];
+    };
+    (1..=25)
+        .filter_map(|day_no| {
+            year.get_day(day_no).map(|day| DayInfo {
+                year_no,
+                day_no,
+                title: None,
+                stars: day.stars(),
+            })
+        })
+        .collect()
+}
+
+// With the titles of the puzzle texts fetched by fetch-puzzle into the
+// given input directory, e.g. "input" or "input/alice" for a profile.
+pub fn days_with_titles(year_no: usize, input_dir: &Path) -> Vec<DayInfo> {
+    days(year_no)
+        .into_iter()
+        .map(|day| DayInfo {
+            title: title(input_dir, year_no, day.day_no),
+            ..day
+        })
+        .collect()
+}
+
+pub fn all_days() -> Vec<DayInfo> {
+    YEARS.iter().flat_map(|year_no| days(*year_no)).collect()
+}
+
+pub fn solve(year_no: usize, day_no: usize, star_no: usize, input: &str) -> Answer {
+    solve_with(year_no, day_no, star_no, input, &Params::default())
+}
+
+pub fn solve_with(
+    year_no: usize,
+    day_no: usize,
+    star_no: usize,
+    input: &str,
+    params: &Params,
+) -> Answer {
+    let day = get_day(year_no, day_no)?;
+    if !(1..=2).contains(&star_no) {
+        return Err(Error::NoSuchStar);
+    }
+    if star_no > day.stars() {
+        return Err(Error::NotImplemented);
+    }
+    if params.check(&day.params()).is_err() {
+        return Err(Error::InvalidParams);
+    }
+    let answer = match day.prepare(input) {
+        Ok(prepared) => prepared.solve(day.as_ref(), star_no, params),
+        Err(e) => format!("parse error: {}", e),
+    };
+    Ok(answer)
+}
+
+// The heading of yearYYYY/dayDD.md, "## Day 1: Historian Hysteria".
+fn title(input_dir: &Path, year_no: usize, day_no: usize) -> Option<String> {
+    let path = input_dir
+        .join(format!("year{:04}", year_no))
+        .join(format!("day{:02}.md", day_no));
+    let text = std::fs::read_to_string(path).ok()?;
+    let heading = text.lines().next()?.strip_prefix("## ")?;
+    let title = heading.split_once(": ").map_or(heading, |(_, title)| title);
+    Some(title.to_string())
+}
+
+#[cfg(all(test, feature = "year2024", feature = "year2025"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_registry() {
+        assert!(YEARS.iter().all(|year_no| ALL_YEARS.contains(year_no)));
+        assert_eq!(YEAR_MAX, 2025);
+        assert_eq!(get_year(2014).err(), Some(Error::NoSuchYear));
+
+        let days = days(2024);
+        assert_eq!(days.len(), 25);
+        assert_eq!((days[0].day_no, days[0].stars), (1, 2));
+        assert_eq!((days[24].day_no, days[24].stars), (25, 1));
+        assert_eq!(self::days(2025)[0].stars, 0);
+        let day_count: usize = YEARS.iter().map(|year_no| self::days(*year_no).len()).sum();
+        assert_eq!(all_days().len(), day_count);
+    }
+
+    #[test]
+    fn test_not_compiled_in() {
+        assert_eq!(missing(2015), Error::NotCompiledIn);
+        assert_eq!(missing(2014), Error::NoSuchYear);
+
+        // only with years left out, e.g. --no-default-features --features
+        // year2024,year2025
+        for year_no in ALL_YEARS.iter().filter(|year_no| !YEARS.contains(year_no)) {
+            assert_eq!(get_year(*year_no).err(), Some(Error::NotCompiledIn));
+            assert_eq!(solve(*year_no, 1, 1, ""), Err(Error::NotCompiledIn));
+            assert!(days(*year_no).is_empty());
+        }
+    }
+
+    #[test]
+    fn test_titles() {
+        let dir =
+            std::env::temp_dir().join(format!("solutions-test-titles-{}", std::process::id()));
+        std::fs::create_dir_all(dir.join("year2024")).unwrap();
+        std::fs::write(
+            dir.join("year2024/day01.md"),
+            "## Day 1: Historian Hysteria\n\nThe Chief Historian...\n",
+        )
+        .unwrap();
+        let days = days_with_titles(2024, &dir);
+        assert_eq!(days[0].title.as_deref(), Some("Historian Hysteria"));
+        assert_eq!(days[1].title, None);
+        assert_eq!(self::days(2024)[0].title, None);
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_solve() {
+        let input = "3   4\n4   3\n2   5\n1   3\n3   9\n3   3\n";
+        assert_eq!(solve(2024, 1, 1, input), Ok(String::from("11")));
+        assert_eq!(solve(2024, 1, 2, input), Ok(String::from("31")));
+        assert_eq!(solve(2014, 1, 1, input), Err(Error::NoSuchYear));
+        assert_eq!(solve(2025, 26, 1, input), Err(Error::NoSuchDay));
+        assert_eq!(solve(2024, 1, 3, input), Err(Error::NoSuchStar));
+        assert_eq!(solve(2024, 25, 2, input), Err(Error::NotImplemented));
+        assert_eq!(solve(2025, 1, 1, input), Err(Error::NotImplemented));
+
+        let params = Params::from([("size", "7"), ("bytes", "12")]);
+        let input = "5,4\n4,2\n4,5\n3,0\n2,1\n6,3\n2,4\n1,5\n0,6\n3,3\n2,6\n5,1\n";
+        assert_eq!(
+            solve_with(2024, 18, 1, input, &params),
+            Ok(String::from("22"))
+        );
+        let params = Params::from([("rows", "7")]);
+        assert_eq!(
+            solve_with(2024, 18, 1, input, &params),
+            Err(Error::InvalidParams)
+        );
+    }
+}
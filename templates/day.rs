@@ -10,15 +10,8 @@ impl Day for Day01 {
     fn star2(&self, _input: &str) -> String {
         String::from("not implemented")
     }
-}
-
-#[cfg(test)]
-mod tests {
-    use super::*;
 
-    #[test]
-    fn ex1() {
-        let d = Day01 {};
-        assert_eq!(d.star1(""), "not implemented");
+    fn stars(&self) -> usize {
+        0
     }
 }
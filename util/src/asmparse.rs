@@ -0,0 +1,203 @@
+use std::collections::HashMap;
+use std::fmt;
+
+// Source handling shared by the parsers of the small assembly dialects
+// (assembunny, wrist): comments, labels and errors with positions.
+//
+//     loop: inc a    ; comments run until the end of the line
+//           jnz a loop
+//
+// A label names the next instruction; lines starting with '#' are
+// directives like "#ip 3".
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ParseError {
+    // both 1-based
+    pub line: usize,
+    pub col: usize,
+    pub msg: String,
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "line {}, column {}: {}", self.line, self.col, self.msg)
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct Token<'a> {
+    pub text: &'a str,
+    pub line: usize,
+    pub col: usize,
+}
+
+impl Token<'_> {
+    pub fn error(&self, msg: String) -> ParseError {
+        ParseError {
+            line: self.line,
+            col: self.col,
+            msg,
+        }
+    }
+
+    pub fn parse<T: std::str::FromStr>(&self, what: &str) -> Result<T, ParseError> {
+        self.text
+            .parse()
+            .map_err(|_| self.error(format!("expected {}, found '{}'", what, self.text)))
+    }
+}
+
+pub struct Source<'a> {
+    pub directives: Vec<Vec<Token<'a>>>,
+    // one entry per instruction: mnemonic and operands
+    pub instructions: Vec<Vec<Token<'a>>>,
+    labels: HashMap<&'a str, usize>,
+}
+
+impl Source<'_> {
+    pub fn new(input: &str) -> Result<Source<'_>, ParseError> {
+        let mut source = Source {
+            directives: vec![],
+            instructions: vec![],
+            labels: HashMap::new(),
+        };
+        for (i, line) in input.lines().enumerate() {
+            let code = line.split(';').next().unwrap();
+            let mut tokens = words(code, i + 1);
+            while let Some(name) = tokens.first().and_then(|t| t.text.strip_suffix(':')) {
+                if !is_identifier(name) {
+                    return Err(tokens[0].error(format!("invalid label '{}'", name)));
+                }
+                if source
+                    .labels
+                    .insert(name, source.instructions.len())
+                    .is_some()
+                {
+                    return Err(tokens[0].error(format!("duplicate label '{}'", name)));
+                }
+                tokens.remove(0);
+            }
+            if tokens.is_empty() {
+                continue;
+            }
+            if tokens[0].text.starts_with('#') {
+                source.directives.push(tokens);
+            } else {
+                source.instructions.push(tokens);
+            }
+        }
+        Ok(source)
+    }
+
+    // Index of the instruction following the label.
+    pub fn label(&self, token: &Token) -> Result<usize, ParseError> {
+        self.labels
+            .get(token.text)
+            .copied()
+            .ok_or_else(|| token.error(format!("undefined label '{}'", token.text)))
+    }
+
+    pub fn is_label(&self, token: &Token) -> bool {
+        self.labels.contains_key(token.text)
+    }
+}
+
+// Checks the operand count, errors point to the first superfluous
+// operand or to the mnemonic.
+pub fn expect_operands(tokens: &[Token], count: usize) -> Result<(), ParseError> {
+    let found = tokens.len() - 1;
+    if found == count {
+        return Ok(());
+    }
+    let at = tokens.get(count + 1).unwrap_or(&tokens[0]);
+    Err(at.error(format!(
+        "'{}' expects {} operand{}, found {}",
+        tokens[0].text,
+        count,
+        if count == 1 { "" } else { "s" },
+        found
+    )))
+}
+
+fn is_identifier(s: &str) -> bool {
+    let mut chars = s.chars();
+    chars.next().is_some_and(|c| c.is_alphabetic() || c == '_')
+        && chars.all(|c| c.is_alphanumeric() || c == '_')
+}
+
+fn words(line: &str, line_no: usize) -> Vec<Token<'_>> {
+    let mut tokens = vec![];
+    let mut start = None;
+    let chars = line
+        .char_indices()
+        .chain(std::iter::once((line.len(), ' ')));
+    for (col, (idx, c)) in chars.enumerate() {
+        match (c.is_whitespace(), start) {
+            (false, None) => {
+                start = Some((idx, col + 1));
+            }
+            (true, Some((s, s_col))) => {
+                tokens.push(Token {
+                    text: &line[s..idx],
+                    line: line_no,
+                    col: s_col,
+                });
+                start = None;
+            }
+            _ => {}
+        }
+    }
+    tokens
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_source() {
+        let input = "#ip 2\n\n  start: inc a ; comment\nend:\n;only comment\nx: y: dec  b";
+        let source = Source::new(input).unwrap();
+        assert_eq!(source.directives.len(), 1);
+        assert_eq!(source.instructions.len(), 2);
+        assert_eq!(
+            source.instructions[1][1],
+            Token {
+                text: "b",
+                line: 6,
+                col: 12
+            }
+        );
+        let label = |text| Token {
+            text,
+            line: 1,
+            col: 1,
+        };
+        assert_eq!(source.label(&label("start")), Ok(0));
+        assert_eq!(source.label(&label("end")), Ok(1));
+        assert_eq!(source.label(&label("y")), Ok(1));
+        assert_eq!(
+            source.label(&label("z")).unwrap_err().to_string(),
+            "line 1, column 1: undefined label 'z'"
+        );
+    }
+
+    #[test]
+    fn test_errors() {
+        let err = Source::new("inc a\n  a: b\n  a: c").err().unwrap();
+        assert_eq!((err.line, err.col), (3, 3));
+        assert_eq!(err.msg, "duplicate label 'a'");
+
+        let err = Source::new("1x: inc a").err().unwrap();
+        assert_eq!(err.msg, "invalid label '1x'");
+
+        let source = Source::new("cpy 1 a b").unwrap();
+        let err = expect_operands(&source.instructions[0], 2).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "line 1, column 9: 'cpy' expects 2 operands, found 3"
+        );
+    }
+}
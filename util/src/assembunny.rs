@@ -1,4 +1,9 @@
-#[derive(Copy, Clone)]
+use crate::asmparse::{expect_operands, ParseError, Source, Token};
+use crate::cancel;
+use std::collections::HashMap;
+use std::fmt;
+
+#[derive(Copy, Clone, Debug, Hash, PartialEq, Eq)]
 pub enum RegId {
     A,
     B,
@@ -6,13 +11,13 @@ pub enum RegId {
     D,
 }
 
-#[derive(Copy, Clone)]
+#[derive(Copy, Clone, Debug, Hash, PartialEq, Eq)]
 pub enum Val {
     Imm(i64),
     Reg(RegId),
 }
 
-#[derive(Copy, Clone)]
+#[derive(Copy, Clone, Debug, Hash, PartialEq, Eq)]
 pub enum Op {
     Nop,
     Cpy(Val, RegId),
@@ -23,6 +28,44 @@ pub enum Op {
     Out(Val),
 }
 
+// Output of a program as found by Computer::clock_signal.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Signal {
+    Halted(Vec<i64>),
+    // the output is prefix followed by cycle, repeated forever
+    Periodic { prefix: Vec<i64>, cycle: Vec<i64> },
+    // neither halted nor repeated within the op limit
+    Unknown(Vec<i64>),
+}
+
+// Loops recognized by the optimizer, executed as a single step.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+enum MacroOp {
+    // inc/dec target; dec counter; jnz counter -2
+    Add {
+        target: RegId,
+        delta: i64,
+        counter: RegId,
+    },
+    // cpy src inner; <add loop over inner>; dec outer; jnz outer -5
+    Mul {
+        target: RegId,
+        delta: i64,
+        src: Val,
+        inner: RegId,
+        outer: RegId,
+    },
+}
+
+impl MacroOp {
+    fn len(&self) -> i64 {
+        match self {
+            MacroOp::Add { .. } => 3,
+            MacroOp::Mul { .. } => 6,
+        }
+    }
+}
+
 pub struct Computer {
     a: i64,
     b: i64,
@@ -30,64 +73,128 @@ pub struct Computer {
     d: i64,
     pc: i64,
     program: Vec<Op>,
+    // macro op starting at each pc, only derived when optimizing
+    macros: Option<Vec<Option<MacroOp>>>,
 }
 
 impl Computer {
     pub fn new(program_str: &str) -> Computer {
-        let program = parse_input(program_str);
-        Computer {
+        Computer::parse(program_str).unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    pub fn parse(program_str: &str) -> Result<Computer, ParseError> {
+        Ok(Computer {
             a: 0,
             b: 0,
             c: 0,
             d: 0,
             pc: 0,
-            program,
-        }
+            program: parse(program_str)?,
+            macros: None,
+        })
+    }
+
+    pub fn program(&self) -> &[Op] {
+        &self.program
+    }
+
+    // Enables the optimizer: add and multiply loops are run as single
+    // macro ops. The pass is re-derived whenever tgl changes the program.
+    pub fn optimize(&mut self) {
+        self.macros = Some(derive_macros(&self.program));
     }
 
     pub fn exec(&mut self, max_out: Option<usize>, max_op: Option<usize>) -> String {
         let mut op_count = 0;
         let mut out_buf = String::new();
-        while self.pc >= 0
-            && self.pc < self.program.len() as i64
+        while self.is_running()
             && (max_op.is_none() || op_count < max_op.unwrap())
             && (max_out.is_none() || out_buf.len() < max_out.unwrap())
         {
-            op_count += 1;
-            match self.program[self.pc as usize] {
-                Op::Nop => {
-                    self.pc += 1;
-                }
-                Op::Cpy(x, y) => {
-                    self.set_reg(y, self.eval(&x));
-                    self.pc += 1;
-                }
-                Op::Inc(x) => {
-                    self.set_reg(x, self.get_reg(x) + 1);
-                    self.pc += 1;
-                }
-                Op::Dec(x) => {
-                    self.set_reg(x, self.get_reg(x) - 1);
-                    self.pc += 1;
-                }
-                Op::Jnz(x, y) => {
-                    if self.eval(&x) != 0 {
-                        self.pc += self.eval(&y);
-                    } else {
-                        self.pc += 1;
-                    }
-                }
-                Op::Tgl(x) => {
-                    self.toggle(self.pc + self.eval(&x));
-                    self.pc += 1;
+            cancel::check();
+            let (ops, out) = self.step();
+            op_count += ops;
+            if let Some(v) = out {
+                out_buf.push_str(&v.to_string());
+            }
+        }
+        out_buf
+    }
+
+    // Runs until the program halts or the machine state (registers, pc and
+    // program) repeats at an out instruction. In the latter case the output
+    // is provably periodic from there on. Gives up after max_op ops.
+    pub fn clock_signal(&mut self, max_op: Option<usize>) -> Signal {
+        let mut seen = HashMap::new();
+        let mut out = vec![];
+        let mut op_count = 0;
+        while self.is_running() {
+            cancel::check();
+            if max_op.is_some_and(|max| op_count >= max) {
+                return Signal::Unknown(out);
+            }
+            if let Op::Out(_) = self.program[self.pc as usize] {
+                let state = (
+                    [self.a, self.b, self.c, self.d, self.pc],
+                    self.program.clone(),
+                );
+                if let Some(&start) = seen.get(&state) {
+                    let cycle = out.split_off(start);
+                    return Signal::Periodic { prefix: out, cycle };
                 }
-                Op::Out(x) => {
-                    out_buf.push_str(&self.eval(&x).to_string());
+                seen.insert(state, out.len());
+            }
+            let (ops, v) = self.step();
+            op_count += ops;
+            out.extend(v);
+        }
+        Signal::Halted(out)
+    }
+
+    fn is_running(&self) -> bool {
+        self.pc >= 0 && self.pc < self.program.len() as i64
+    }
+
+    // Executes the op (or macro op) at pc. Returns the number of plain ops
+    // executed and the value sent by out, if any.
+    fn step(&mut self) -> (usize, Option<i64>) {
+        if let Some(ops) = self.exec_macro() {
+            return (ops, None);
+        }
+        let mut out = None;
+        match self.program[self.pc as usize] {
+            Op::Nop => {
+                self.pc += 1;
+            }
+            Op::Cpy(x, y) => {
+                self.set_reg(y, self.eval(&x));
+                self.pc += 1;
+            }
+            Op::Inc(x) => {
+                self.set_reg(x, self.get_reg(x) + 1);
+                self.pc += 1;
+            }
+            Op::Dec(x) => {
+                self.set_reg(x, self.get_reg(x) - 1);
+                self.pc += 1;
+            }
+            Op::Jnz(x, y) => {
+                if self.eval(&x) != 0 {
+                    self.pc += self.eval(&y);
+                } else {
                     self.pc += 1;
                 }
             }
+            Op::Tgl(x) => {
+                self.toggle(self.pc + self.eval(&x));
+                self.pc += 1;
+            }
+            Op::Out(x) => {
+                out = Some(self.eval(&x));
+                self.pc += 1;
+            }
         }
-        out_buf
+        (1, out)
     }
 
     pub fn get_reg(&self, id: RegId) -> i64 {
@@ -116,6 +223,46 @@ impl Computer {
         }
     }
 
+    // Runs the macro op at pc, if any. Returns the number of plain ops it
+    // stands for, or None if the loop would not terminate normally (counters
+    // not positive), in which case the plain ops are executed.
+    fn exec_macro(&mut self) -> Option<usize> {
+        let m = self.macros.as_ref()?[self.pc as usize]?;
+        let ops = match m {
+            MacroOp::Add {
+                target,
+                delta,
+                counter,
+            } => {
+                let n = self.get_reg(counter);
+                if n <= 0 {
+                    return None;
+                }
+                self.set_reg(target, self.get_reg(target) + delta * n);
+                self.set_reg(counter, 0);
+                3 * n
+            }
+            MacroOp::Mul {
+                target,
+                delta,
+                src,
+                inner,
+                outer,
+            } => {
+                let (n, k) = (self.eval(&src), self.get_reg(outer));
+                if n <= 0 || k <= 0 {
+                    return None;
+                }
+                self.set_reg(target, self.get_reg(target) + delta * n * k);
+                self.set_reg(inner, 0);
+                self.set_reg(outer, 0);
+                k * (3 * n + 3)
+            }
+        };
+        self.pc += m.len();
+        Some(ops as usize)
+    }
+
     fn eval(&self, val: &Val) -> i64 {
         match val {
             Val::Imm(x) => *x,
@@ -144,42 +291,373 @@ impl Computer {
                 },
             };
             self.program[pos as usize] = new_op;
+            if self.macros.is_some() {
+                self.optimize();
+            }
         }
     }
 }
 
+fn derive_macros(program: &[Op]) -> Vec<Option<MacroOp>> {
+    (0..program.len())
+        .map(|pc| mul_loop(&program[pc..]).or_else(|| add_loop(&program[pc..])))
+        .collect()
+}
+
+fn add_loop(ops: &[Op]) -> Option<MacroOp> {
+    match *ops.get(..3)? {
+        [x, Op::Dec(counter), Op::Jnz(Val::Reg(j), Val::Imm(-2))]
+        | [Op::Dec(counter), x, Op::Jnz(Val::Reg(j), Val::Imm(-2))]
+            if counter == j =>
+        {
+            let (target, delta) = match x {
+                Op::Inc(t) => (t, 1),
+                Op::Dec(t) => (t, -1),
+                _ => return None,
+            };
+            (target != counter).then_some(MacroOp::Add {
+                target,
+                delta,
+                counter,
+            })
+        }
+        _ => None,
+    }
+}
+
+fn mul_loop(ops: &[Op]) -> Option<MacroOp> {
+    let (src, inner, outer) = match *ops.get(..6)? {
+        [Op::Cpy(src, inner), _, _, _, Op::Dec(outer), Op::Jnz(Val::Reg(j), Val::Imm(-5))]
+            if outer == j =>
+        {
+            (src, inner, outer)
+        }
+        _ => return None,
+    };
+    match add_loop(&ops[1..4])? {
+        MacroOp::Add {
+            target,
+            delta,
+            counter,
+        } if counter == inner
+            && outer != target
+            && outer != inner
+            && ![Val::Reg(target), Val::Reg(inner), Val::Reg(outer)].contains(&src) =>
+        {
+            Some(MacroOp::Mul {
+                target,
+                delta,
+                src,
+                inner,
+                outer,
+            })
+        }
+        _ => None,
+    }
+}
+
 fn to_regid(s: &str) -> Option<RegId> {
-    let c = s.chars().next().unwrap();
-    match c {
-        'a' => Some(RegId::A),
-        'b' => Some(RegId::B),
-        'c' => Some(RegId::C),
-        'd' => Some(RegId::D),
+    match s {
+        "a" => Some(RegId::A),
+        "b" => Some(RegId::B),
+        "c" => Some(RegId::C),
+        "d" => Some(RegId::D),
         _ => None,
     }
 }
 
-fn parse_val(s: &str) -> Val {
-    match s.parse::<i64>() {
-        Ok(x) => Val::Imm(x),
-        Err(_) => Val::Reg(to_regid(s).unwrap()),
+// Programs may contain comments and labels (see asmparse); labels can be
+// used as the offset operand of jnz and tgl.
+pub fn parse(input: &str) -> Result<Vec<Op>, ParseError> {
+    let source = Source::new(input)?;
+    if let Some(directive) = source.directives.first() {
+        let t = directive[0];
+        return Err(t.error(format!("unknown directive '{}'", t.text)));
+    }
+    source
+        .instructions
+        .iter()
+        .enumerate()
+        .map(|(pc, tokens)| parse_op(&source, pc, tokens))
+        .collect()
+}
+
+fn parse_op(source: &Source, pc: usize, tokens: &[Token]) -> Result<Op, ParseError> {
+    let mnemonic = tokens[0];
+    let arity = match mnemonic.text {
+        "nop" => 0,
+        "inc" | "dec" | "tgl" | "out" => 1,
+        "cpy" | "jnz" => 2,
+        _ => {
+            return Err(mnemonic.error(format!("unknown instruction '{}'", mnemonic.text)));
+        }
+    };
+    expect_operands(tokens, arity)?;
+
+    let reg = |t: &Token| {
+        to_regid(t.text).ok_or_else(|| t.error(format!("expected register, found '{}'", t.text)))
+    };
+    let val = |t: &Token| match to_regid(t.text) {
+        Some(r) => Ok(Val::Reg(r)),
+        None => t.parse("number or register").map(Val::Imm),
+    };
+    let offset = |t: &Token| match to_regid(t.text) {
+        Some(r) => Ok(Val::Reg(r)),
+        None if source.is_label(t) => Ok(Val::Imm(source.label(t)? as i64 - pc as i64)),
+        None => t.parse("number, register or label").map(Val::Imm),
+    };
+
+    let args = &tokens[1..];
+    Ok(match mnemonic.text {
+        "nop" => Op::Nop,
+        "cpy" => Op::Cpy(val(&args[0])?, reg(&args[1])?),
+        "inc" => Op::Inc(reg(&args[0])?),
+        "dec" => Op::Dec(reg(&args[0])?),
+        "jnz" => Op::Jnz(val(&args[0])?, offset(&args[1])?),
+        "tgl" => Op::Tgl(offset(&args[0])?),
+        "out" => Op::Out(val(&args[0])?),
+        _ => unreachable!(),
+    })
+}
+
+impl fmt::Display for RegId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            RegId::A => "a",
+            RegId::B => "b",
+            RegId::C => "c",
+            RegId::D => "d",
+        };
+        write!(f, "{}", name)
     }
 }
 
-fn parse_input(input: &str) -> Vec<Op> {
-    input
-        .lines()
-        .filter_map(|line| {
-            let words: Vec<_> = line.split_whitespace().collect();
-            match words[0] {
-                "cpy" => Some(Op::Cpy(parse_val(words[1]), to_regid(words[2]).unwrap())),
-                "inc" => Some(Op::Inc(to_regid(words[1]).unwrap())),
-                "dec" => Some(Op::Dec(to_regid(words[1]).unwrap())),
-                "jnz" => Some(Op::Jnz(parse_val(words[1]), parse_val(words[2]))),
-                "tgl" => Some(Op::Tgl(parse_val(words[1]))),
-                "out" => Some(Op::Out(parse_val(words[1]))),
-                _ => None,
+impl fmt::Display for Val {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Val::Imm(x) => write!(f, "{}", x),
+            Val::Reg(r) => write!(f, "{}", r),
+        }
+    }
+}
+
+impl fmt::Display for Op {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Op::Nop => write!(f, "nop"),
+            Op::Cpy(x, y) => write!(f, "cpy {} {}", x, y),
+            Op::Inc(x) => write!(f, "inc {}", x),
+            Op::Dec(x) => write!(f, "dec {}", x),
+            Op::Jnz(x, y) => write!(f, "jnz {} {}", x, y),
+            Op::Tgl(x) => write!(f, "tgl {}", x),
+            Op::Out(x) => write!(f, "out {}", x),
+        }
+    }
+}
+
+// The (possibly toggled) program, one instruction per line.
+impl fmt::Display for Computer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, op) in self.program.iter().enumerate() {
+            if i > 0 {
+                writeln!(f)?;
             }
-        })
-        .collect()
+            write!(f, "{}", op)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const FACTORIAL: &str = r#"cpy a b
+dec b
+cpy a d
+cpy 0 a
+cpy b c
+inc a
+dec c
+jnz c -2
+dec d
+jnz d -5
+dec b
+cpy b c
+cpy c d
+dec d
+inc c
+jnz d -2
+tgl c
+cpy -16 c
+jnz 1 c
+cpy 73 c
+jnz 71 d
+inc a
+inc d
+jnz d -2
+inc c
+jnz c -5"#;
+
+    fn cross_check(program: &str, a: i64) -> i64 {
+        let mut plain = Computer::new(program);
+        plain.set_reg(RegId::A, a);
+        let out_plain = plain.exec(None, None);
+
+        let mut optimized = Computer::new(program);
+        optimized.set_reg(RegId::A, a);
+        optimized.optimize();
+        let out_optimized = optimized.exec(None, None);
+
+        assert_eq!(out_plain, out_optimized);
+        assert_eq!(plain.program, optimized.program);
+        for r in [RegId::A, RegId::B, RegId::C, RegId::D] {
+            assert_eq!(plain.get_reg(r), optimized.get_reg(r));
+        }
+        optimized.get_reg(RegId::A)
+    }
+
+    #[test]
+    fn test_derive() {
+        let program = parse(FACTORIAL).unwrap();
+        let macros = derive_macros(&program);
+        assert_eq!(
+            macros[4],
+            Some(MacroOp::Mul {
+                target: RegId::A,
+                delta: 1,
+                src: Val::Reg(RegId::B),
+                inner: RegId::C,
+                outer: RegId::D
+            })
+        );
+        assert_eq!(
+            macros[13],
+            Some(MacroOp::Add {
+                target: RegId::C,
+                delta: 1,
+                counter: RegId::D
+            })
+        );
+        // not a loop yet: jnz 71 d and inc d are toggled at runtime
+        assert_eq!(macros[19], None);
+    }
+
+    #[test]
+    fn test_cross_check() {
+        assert_eq!(
+            cross_check("cpy 41 a\ninc a\ninc a\ndec a\njnz a 2\ndec a", 0),
+            42
+        );
+        assert_eq!(
+            cross_check(
+                "cpy 7 b\ncpy 6 d\ncpy b c\ndec a\ndec c\njnz c -2\ndec d\njnz d -5",
+                0
+            ),
+            -42
+        );
+        for a in 6..=7 {
+            let factorial: i64 = (1..=a).product();
+            assert_eq!(cross_check(FACTORIAL, a), factorial + 73 * 71);
+        }
+    }
+
+    #[test]
+    fn test_tgl_in_region() {
+        // the second pass through the add loop runs with inc a toggled to
+        // dec a, the third one with it toggled back
+        let program = r#"cpy 3 d
+cpy 5 c
+inc a
+dec c
+jnz c -2
+cpy -4 b
+tgl b
+dec d
+jnz d -7"#;
+        assert_eq!(cross_check(program, 0), 5);
+    }
+
+    #[test]
+    fn test_clock_signal() {
+        let mut computer = Computer::new("cpy 0 a\nout a\ninc a\nout a\ndec a\njnz 1 -4");
+        assert_eq!(
+            computer.clock_signal(None),
+            Signal::Periodic {
+                prefix: vec![],
+                cycle: vec![0, 1]
+            }
+        );
+
+        let mut computer = Computer::new("out 7\ncpy 0 a\nout a\njnz 1 -1");
+        assert_eq!(
+            computer.clock_signal(None),
+            Signal::Periodic {
+                prefix: vec![7],
+                cycle: vec![0]
+            }
+        );
+
+        let mut computer = Computer::new("out 5\nout 6");
+        assert_eq!(computer.clock_signal(None), Signal::Halted(vec![5, 6]));
+
+        // the state never repeats
+        let mut computer = Computer::new("inc a\nout a\njnz 1 -2");
+        assert_eq!(
+            computer.clock_signal(Some(9)),
+            Signal::Unknown(vec![1, 2, 3])
+        );
+    }
+
+    #[test]
+    fn test_parse() {
+        let labelled = r#"; a = b * d
+        cpy 7 b
+        cpy 6 d
+outer:  cpy b c
+inner:  inc a       ; add c times
+        dec c
+        jnz c inner
+        dec d
+        jnz d outer
+        tgl end
+        jnz 1 -1
+end:"#;
+        let computer = Computer::parse(labelled).unwrap();
+        let text = computer.to_string();
+        assert_eq!(
+            text,
+            "cpy 7 b\ncpy 6 d\ncpy b c\ninc a\ndec c\njnz c -2\ndec d\njnz d -5\ntgl 2\njnz 1 -1"
+        );
+        assert_eq!(parse(&text).unwrap(), computer.program());
+        assert_eq!(parse(FACTORIAL).unwrap().len(), 26);
+
+        let error = |input| parse(input).unwrap_err().to_string();
+        assert_eq!(
+            error("inc a\ncpy 1 x"),
+            "line 2, column 7: expected register, found 'x'"
+        );
+        assert_eq!(
+            error("  mul a b"),
+            "line 1, column 3: unknown instruction 'mul'"
+        );
+        assert_eq!(
+            error("jnz a nowhere"),
+            "line 1, column 7: expected number, register or label, found 'nowhere'"
+        );
+        assert_eq!(
+            error("inc"),
+            "line 1, column 1: 'inc' expects 1 operand, found 0"
+        );
+        assert_eq!(error("#ip 1"), "line 1, column 1: unknown directive '#ip'");
+    }
+
+    #[test]
+    fn test_optimized_factorial() {
+        let mut computer = Computer::new(FACTORIAL);
+        computer.set_reg(RegId::A, 12);
+        computer.optimize();
+        computer.exec(None, None);
+        assert_eq!(computer.get_reg(RegId::A), 479001600 + 73 * 71);
+    }
 }
@@ -0,0 +1,116 @@
+use std::cell::{Cell, RefCell};
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+// Cooperative cancellation. The runner installs a token for the thread
+// running a solution and cancels it when the time budget is exceeded;
+// long-running loops call check(), which unwinds out of the solution once
+// the token is cancelled. Without an installed token check() does nothing.
+
+#[derive(Clone, Debug, Default)]
+pub struct CancelToken {
+    flag: Arc<AtomicBool>,
+}
+
+// Unwinding payload of a cancelled solution.
+#[derive(Debug)]
+pub struct Cancelled;
+
+impl CancelToken {
+    pub fn new() -> CancelToken {
+        CancelToken::default()
+    }
+
+    pub fn cancel(&self) {
+        self.flag.store(true, Ordering::Relaxed);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.flag.load(Ordering::Relaxed)
+    }
+}
+
+thread_local! {
+    static CURRENT: RefCell<Option<CancelToken>> = const { RefCell::new(None) };
+    static CALLS: Cell<u32> = const { Cell::new(0) };
+}
+
+// The token checked by this thread from now on.
+pub fn install(token: CancelToken) {
+    CURRENT.with(|current| *current.borrow_mut() = Some(token));
+}
+
+pub fn is_cancelled() -> bool {
+    CURRENT.with(|current| current.borrow().as_ref().is_some_and(|t| t.is_cancelled()))
+}
+
+// Cheap enough for inner loops: the token is only looked at every 1024
+// calls.
+pub fn check() {
+    let calls = CALLS.with(|c| {
+        let n = c.get().wrapping_add(1);
+        c.set(n);
+        n
+    });
+    if calls.is_multiple_of(1024) {
+        check_now();
+    }
+}
+
+// For loops with expensive iterations.
+pub fn check_now() {
+    if is_cancelled() {
+        // no panic message, this is not an error
+        panic::resume_unwind(Box::new(Cancelled));
+    }
+}
+
+// Runs f, None if it was cancelled. Other panics are passed on.
+pub fn run<R>(f: impl FnOnce() -> R) -> Option<R> {
+    match panic::catch_unwind(AssertUnwindSafe(f)) {
+        Ok(r) => Some(r),
+        Err(payload) if payload.is::<Cancelled>() => None,
+        Err(payload) => panic::resume_unwind(payload),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::intcode::Intcode;
+
+    fn loop_forever() {
+        // jump-if-true 1 to 0
+        Intcode::new(&[1105, 1, 0]).run();
+    }
+
+    #[test]
+    fn test_cancel() {
+        let token = CancelToken::new();
+        let handle = {
+            let token = token.clone();
+            std::thread::spawn(move || {
+                install(token);
+                run(loop_forever)
+            })
+        };
+        token.cancel();
+        assert!(handle.join().unwrap().is_none());
+    }
+
+    #[test]
+    fn test_not_cancelled() {
+        assert!(!is_cancelled());
+        install(CancelToken::new());
+        assert_eq!(
+            run(|| {
+                (0..5000).for_each(|_| check());
+                42
+            }),
+            Some(42)
+        );
+        let other = std::thread::spawn(|| run(|| panic!("failed")));
+        assert!(other.join().is_err());
+    }
+}
@@ -1,23 +1,356 @@
-use std::cmp::Ordering;
+// Lazy enumeration of subsets, combinations, permutations and equal-sum
+// partitions. The iterators yield indices into the given items (so equal
+// items stay distinguishable, as the containers of 2015 day 17), or the
+// items themselves where the order is all that matters. The count_*
+// functions only count, by dynamic programming, for inputs where the
+// enumeration would be too large.
 
+// All values of the non-empty subsets summing up to target.
 pub fn get_combos(target: usize, containers: &[usize]) -> Vec<Vec<usize>> {
-    let mut combos = vec![];
-    for (idx, container) in containers.iter().enumerate() {
-        match target.cmp(container) {
-            Ordering::Equal => {
-                combos.push(vec![*container]);
-            }
-            Ordering::Greater => {
-                let new_target = target - container;
-                let new_containers = containers[idx + 1..].to_vec();
-                for sub_combo in get_combos(new_target, &new_containers) {
-                    let mut combo = vec![*container];
-                    combo.extend(&sub_combo);
-                    combos.push(combo);
+    subset_sums(containers, target)
+        .map(|combo| combo.iter().map(|i| containers[*i]).collect())
+        .collect()
+}
+
+// Non-empty subsets summing up to target, as increasing indices.
+pub fn subset_sums(items: &[usize], target: usize) -> SubsetSums<'_> {
+    SubsetSums {
+        items,
+        target,
+        cur: vec![],
+        sum: 0,
+        done: false,
+    }
+}
+
+pub struct SubsetSums<'a> {
+    items: &'a [usize],
+    target: usize,
+    cur: Vec<usize>,
+    sum: usize,
+    done: bool,
+}
+
+impl SubsetSums<'_> {
+    fn push_from(&mut self, from: usize) -> bool {
+        for i in from..self.items.len() {
+            if self.sum + self.items[i] <= self.target {
+                self.cur.push(i);
+                self.sum += self.items[i];
+                return true;
+            }
+        }
+        false
+    }
+
+    // next subset in depth-first order, false when exhausted
+    fn advance(&mut self) -> bool {
+        let from = self.cur.last().map_or(0, |i| i + 1);
+        if self.push_from(from) {
+            return true;
+        }
+        while let Some(last) = self.cur.pop() {
+            self.sum -= self.items[last];
+            if self.push_from(last + 1) {
+                return true;
+            }
+        }
+        false
+    }
+}
+
+impl Iterator for SubsetSums<'_> {
+    type Item = Vec<usize>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while !self.done {
+            if !self.advance() {
+                self.done = true;
+            } else if self.sum == self.target {
+                return Some(self.cur.clone());
+            }
+        }
+        None
+    }
+}
+
+// k-combinations of 0..n as increasing indices.
+pub fn combinations(n: usize, k: usize) -> Combinations<fn(&[usize]) -> bool> {
+    combinations_where(n, k, |_| true)
+}
+
+// k-combinations of 0..n, where every prefix has to satisfy pred; a
+// rejected prefix is not extended any further.
+pub fn combinations_where<F>(n: usize, k: usize, pred: F) -> Combinations<F>
+where
+    F: FnMut(&[usize]) -> bool,
+{
+    Combinations {
+        n,
+        k,
+        pred,
+        cur: vec![],
+        started: false,
+        done: false,
+    }
+}
+
+pub struct Combinations<F> {
+    n: usize,
+    k: usize,
+    pred: F,
+    cur: Vec<usize>,
+    started: bool,
+    done: bool,
+}
+
+impl<F: FnMut(&[usize]) -> bool> Combinations<F> {
+    fn push_from(&mut self, from: usize) -> bool {
+        for i in from..self.n {
+            // not enough indices left to complete the combination
+            if self.n - i < self.k - self.cur.len() {
+                break;
+            }
+            self.cur.push(i);
+            if (self.pred)(&self.cur) {
+                return true;
+            }
+            self.cur.pop();
+        }
+        false
+    }
+}
+
+impl<F: FnMut(&[usize]) -> bool> Iterator for Combinations<F> {
+    type Item = Vec<usize>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let mut descend = !self.started;
+        self.started = true;
+        while !self.done {
+            if descend {
+                if self.cur.len() == self.k {
+                    return Some(self.cur.clone());
                 }
+                let from = self.cur.last().map_or(0, |i| i + 1);
+                descend = self.push_from(from);
+            } else if let Some(last) = self.cur.pop() {
+                descend = self.push_from(last + 1);
+            } else {
+                self.done = true;
             }
-            Ordering::Less => {}
         }
+        None
+    }
+}
+
+// All orderings of items, by Heap's algorithm: each permutation differs
+// from the previous one by a single swap.
+pub fn permutations<T: Clone>(items: Vec<T>) -> Permutations<T> {
+    Permutations {
+        c: vec![0; items.len()],
+        items,
+        i: 1,
+        started: false,
+    }
+}
+
+pub struct Permutations<T> {
+    items: Vec<T>,
+    c: Vec<usize>,
+    i: usize,
+    started: bool,
+}
+
+impl<T: Clone> Iterator for Permutations<T> {
+    type Item = Vec<T>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if !self.started {
+            self.started = true;
+            return Some(self.items.clone());
+        }
+        while self.i < self.items.len() {
+            let i = self.i;
+            if self.c[i] < i {
+                if i.is_multiple_of(2) {
+                    self.items.swap(0, i);
+                } else {
+                    self.items.swap(self.c[i], i);
+                }
+                self.c[i] += 1;
+                self.i = 1;
+                return Some(self.items.clone());
+            }
+            self.c[i] = 0;
+            self.i += 1;
+        }
+        None
+    }
+}
+
+// Partitions of items into k groups with equal sums, as indices per group.
+// Groups are ordered by their first item, so each partition comes up once.
+pub fn equal_partitions(items: &[usize], k: usize) -> EqualPartitions<'_> {
+    let total: usize = items.iter().sum();
+    EqualPartitions {
+        items,
+        target: total.checked_div(k).unwrap_or(0),
+        assign: vec![],
+        sums: vec![0; k],
+        started: false,
+        done: k == 0 || !total.is_multiple_of(k),
+    }
+}
+
+pub struct EqualPartitions<'a> {
+    items: &'a [usize],
+    target: usize,
+    // group of each item assigned so far
+    assign: Vec<usize>,
+    sums: Vec<usize>,
+    started: bool,
+    done: bool,
+}
+
+impl EqualPartitions<'_> {
+    // puts the next item into the first group from `from` on where it fits
+    fn place(&mut self, from: usize) -> bool {
+        let item = self.items[self.assign.len()];
+        // a new group may only be opened after all previous ones
+        let open = self.assign.iter().max().map_or(0, |g| g + 1);
+        for g in from..=open.min(self.sums.len() - 1) {
+            if self.sums[g] + item <= self.target {
+                self.sums[g] += item;
+                self.assign.push(g);
+                return true;
+            }
+        }
+        false
+    }
+
+    fn groups(&self) -> Vec<Vec<usize>> {
+        let mut groups = vec![vec![]; self.sums.len()];
+        for (i, g) in self.assign.iter().enumerate() {
+            groups[*g].push(i);
+        }
+        groups
+    }
+}
+
+impl Iterator for EqualPartitions<'_> {
+    type Item = Vec<Vec<usize>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let mut descend = !self.started;
+        self.started = true;
+        while !self.done {
+            if descend {
+                // all sums are at most target and add up to k * target
+                if self.assign.len() == self.items.len() {
+                    return Some(self.groups());
+                }
+                descend = self.place(0);
+            } else if let Some(g) = self.assign.pop() {
+                self.sums[g] -= self.items[self.assign.len()];
+                descend = self.place(g + 1);
+            } else {
+                self.done = true;
+            }
+        }
+        None
+    }
+}
+
+// Number of non-empty subsets summing up to target.
+pub fn count_subset_sums(items: &[usize], target: usize) -> u64 {
+    let mut counts = vec![0u64; target + 1];
+    counts[0] = 1;
+    for item in items {
+        for s in (*item..=target).rev() {
+            counts[s] += counts[s - item];
+        }
+    }
+    counts[target] - u64::from(target == 0)
+}
+
+// Number of subsets summing up to target, by subset size (0..=items.len()).
+pub fn count_subset_sums_by_size(items: &[usize], target: usize) -> Vec<u64> {
+    // counts[size][sum]
+    let mut counts = vec![vec![0u64; target + 1]; items.len() + 1];
+    counts[0][0] = 1;
+    for (n, item) in items.iter().enumerate() {
+        for size in (1..=n + 1).rev() {
+            for s in (*item..=target).rev() {
+                counts[size][s] += counts[size - 1][s - item];
+            }
+        }
+    }
+    counts.iter().map(|c| c[target]).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_subset_sums() {
+        let containers = [20, 15, 10, 5, 5];
+        let combos: Vec<_> = subset_sums(&containers, 25).collect();
+        assert_eq!(
+            combos,
+            vec![vec![0, 3], vec![0, 4], vec![1, 2], vec![1, 3, 4]]
+        );
+        assert_eq!(get_combos(25, &containers)[3], vec![15, 5, 5]);
+        assert_eq!(count_subset_sums(&containers, 25), 4);
+        assert_eq!(
+            count_subset_sums_by_size(&containers, 25),
+            vec![0, 0, 3, 1, 0, 0]
+        );
+        assert_eq!(subset_sums(&containers, 100).count(), 0);
+    }
+
+    #[test]
+    fn test_combinations() {
+        let all: Vec<_> = combinations(4, 2).collect();
+        assert_eq!(all.len(), 6);
+        assert_eq!(all[0], vec![0, 1]);
+        assert_eq!(all[5], vec![2, 3]);
+        assert_eq!(combinations(3, 0).collect::<Vec<_>>(), vec![vec![]]);
+        assert_eq!(combinations(2, 3).count(), 0);
+
+        // no two neighbouring indices
+        let sparse: Vec<_> =
+            combinations_where(5, 3, |c| c.len() < 2 || c[c.len() - 1] - c[c.len() - 2] > 1)
+                .collect();
+        assert_eq!(sparse, vec![vec![0, 2, 4]]);
+    }
+
+    #[test]
+    fn test_permutations() {
+        let perms: Vec<_> = permutations(vec![1, 2, 3]).collect();
+        assert_eq!(perms.len(), 6);
+        let mut sorted = perms.clone();
+        sorted.sort();
+        sorted.dedup();
+        assert_eq!(sorted.len(), 6);
+        assert_eq!(permutations(Vec::<u8>::new()).count(), 1);
+        assert_eq!(permutations((0..6).collect()).count(), 720);
+    }
+
+    #[test]
+    fn test_equal_partitions() {
+        let items = [1, 2, 3, 4, 5, 7, 8, 9, 10, 11];
+        let first = equal_partitions(&items, 3).next().unwrap();
+        for group in &first {
+            assert_eq!(group.iter().map(|i| items[*i]).sum::<usize>(), 20);
+        }
+        assert!(equal_partitions(&items, 4).next().is_some());
+        assert_eq!(equal_partitions(&items, 7).count(), 0);
+        assert_eq!(
+            equal_partitions(&[1, 1, 2], 2).collect::<Vec<_>>(),
+            vec![vec![vec![0, 1], vec![2]]]
+        );
+        assert_eq!(equal_partitions(&[2, 2, 2, 2], 2).count(), 3);
     }
-    combos
 }
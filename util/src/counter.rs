@@ -0,0 +1,210 @@
+use std::collections::HashMap;
+use std::hash::Hash;
+use std::ops::{Add, AddAssign, Index, Sub, SubAssign};
+
+// Multiset counting occurrences. Only positive counts are stored, so two
+// counters are equal iff they count the same items.
+//
+// Ties in the sorted views are broken by the key, ascending, like the
+// checksums of 2016 day 4.
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Counter<T: Eq + Hash> {
+    counts: HashMap<T, usize>,
+}
+
+impl<T: Eq + Hash> Default for Counter<T> {
+    fn default() -> Self {
+        Counter {
+            counts: HashMap::new(),
+        }
+    }
+}
+
+impl<T: Eq + Hash> Counter<T> {
+    pub fn new() -> Counter<T> {
+        Counter::default()
+    }
+
+    pub fn add(&mut self, item: T) {
+        self.add_n(item, 1);
+    }
+
+    pub fn add_n(&mut self, item: T, n: usize) {
+        if n > 0 {
+            *self.counts.entry(item).or_insert(0) += n;
+        }
+    }
+
+    // Removes up to n occurrences.
+    pub fn remove_n(&mut self, item: &T, n: usize) {
+        if let Some(count) = self.counts.get_mut(item) {
+            *count = count.saturating_sub(n);
+            if *count == 0 {
+                self.counts.remove(item);
+            }
+        }
+    }
+
+    pub fn get(&self, item: &T) -> usize {
+        self.counts.get(item).copied().unwrap_or(0)
+    }
+
+    // number of distinct items
+    pub fn len(&self) -> usize {
+        self.counts.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.counts.is_empty()
+    }
+
+    // number of items, counting repetitions
+    pub fn total(&self) -> usize {
+        self.counts.values().sum()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (&T, usize)> {
+        self.counts.iter().map(|(k, v)| (k, *v))
+    }
+
+    // Counts only, largest first, e.g. [3, 2] for a full house.
+    pub fn counts_sorted(&self) -> Vec<usize> {
+        let mut counts: Vec<_> = self.counts.values().copied().collect();
+        counts.sort_unstable_by(|a, b| b.cmp(a));
+        counts
+    }
+
+    // Every item occurs in other at least as often.
+    pub fn is_subset(&self, other: &Counter<T>) -> bool {
+        self.iter().all(|(k, v)| v <= other.get(k))
+    }
+
+    pub fn into_map(self) -> HashMap<T, usize> {
+        self.counts
+    }
+}
+
+impl<T: Eq + Hash + Ord> Counter<T> {
+    // By count descending, then by key.
+    pub fn most_common(&self) -> Vec<(&T, usize)> {
+        let mut v: Vec<_> = self.iter().collect();
+        v.sort_unstable_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(b.0)));
+        v
+    }
+
+    // By count ascending, then by key.
+    pub fn least_common(&self) -> Vec<(&T, usize)> {
+        let mut v: Vec<_> = self.iter().collect();
+        v.sort_unstable_by(|a, b| a.1.cmp(&b.1).then_with(|| a.0.cmp(b.0)));
+        v
+    }
+
+    pub fn most(&self) -> Option<&T> {
+        self.iter()
+            .min_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(b.0)))
+            .map(|(k, _)| k)
+    }
+
+    pub fn least(&self) -> Option<&T> {
+        self.iter()
+            .min_by(|a, b| a.1.cmp(&b.1).then_with(|| a.0.cmp(b.0)))
+            .map(|(k, _)| k)
+    }
+}
+
+impl<T: Eq + Hash> Index<&T> for Counter<T> {
+    type Output = usize;
+
+    fn index(&self, item: &T) -> &usize {
+        self.counts.get(item).unwrap_or(&0)
+    }
+}
+
+impl<T: Eq + Hash> FromIterator<T> for Counter<T> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let mut counter = Counter::new();
+        counter.extend(iter);
+        counter
+    }
+}
+
+impl<T: Eq + Hash> Extend<T> for Counter<T> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        for item in iter {
+            self.add(item);
+        }
+    }
+}
+
+impl<T: Eq + Hash> AddAssign for Counter<T> {
+    fn add_assign(&mut self, other: Counter<T>) {
+        for (k, v) in other.counts {
+            self.add_n(k, v);
+        }
+    }
+}
+
+// Subtraction drops counts reaching zero, like multiset difference.
+impl<T: Eq + Hash> SubAssign for Counter<T> {
+    fn sub_assign(&mut self, other: Counter<T>) {
+        for (k, v) in other.counts {
+            self.remove_n(&k, v);
+        }
+    }
+}
+
+impl<T: Eq + Hash> Add for Counter<T> {
+    type Output = Counter<T>;
+
+    fn add(mut self, other: Counter<T>) -> Counter<T> {
+        self += other;
+        self
+    }
+}
+
+impl<T: Eq + Hash> Sub for Counter<T> {
+    type Output = Counter<T>;
+
+    fn sub(mut self, other: Counter<T>) -> Counter<T> {
+        self -= other;
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_views() {
+        let c: Counter<char> = "aaaaabbbzyx".chars().collect();
+        assert_eq!(c.len(), 5);
+        assert_eq!(c.total(), 11);
+        assert_eq!(c[&'b'], 3);
+        assert_eq!(c[&'q'], 0);
+        let top: String = c.most_common().iter().take(5).map(|(k, _)| **k).collect();
+        assert_eq!(top, "abxyz");
+        assert_eq!(c.least_common()[0], (&'x', 1));
+        assert_eq!(c.most(), Some(&'a'));
+        assert_eq!(c.least(), Some(&'x'));
+        assert_eq!(c.counts_sorted(), vec![5, 3, 1, 1, 1]);
+        assert_eq!(Counter::<char>::new().most(), None);
+    }
+
+    #[test]
+    fn test_arithmetic() {
+        let a: Counter<_> = [1, 1, 2].into_iter().collect();
+        let b: Counter<_> = [1, 3].into_iter().collect();
+        let sum = a.clone() + b.clone();
+        assert_eq!(sum, [1, 1, 1, 2, 3].into_iter().collect());
+        assert!(a.is_subset(&sum));
+        assert!(!sum.is_subset(&a));
+
+        let diff = a.clone() - b;
+        assert_eq!(diff, [1, 2].into_iter().collect());
+        let empty = diff.clone() - a;
+        assert!(empty.is_empty());
+        assert_eq!(empty, Counter::new());
+    }
+}
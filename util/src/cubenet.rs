@@ -0,0 +1,300 @@
+use crate::grid2d::{Coords, Direction, Grid2D};
+use std::collections::VecDeque;
+use std::ops::{Add, Mul, Neg, Sub};
+
+// Folding of a 2D cube net (any of the 11 unfoldings) into a cube.
+//
+// Every face gets a 3D frame (outward normal, direction of +x and of +y in
+// the net), derived by rolling the cube from face to face across the net.
+// Cell centers are placed on a cube spanning -size..size in doubled units,
+// which turns wrapping over an edge into simple vector arithmetic.
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+struct Vec3(i64, i64, i64);
+
+impl Add for Vec3 {
+    type Output = Self;
+
+    fn add(self, other: Self) -> Self {
+        Self(self.0 + other.0, self.1 + other.1, self.2 + other.2)
+    }
+}
+
+impl Sub for Vec3 {
+    type Output = Self;
+
+    fn sub(self, other: Self) -> Self {
+        Self(self.0 - other.0, self.1 - other.1, self.2 - other.2)
+    }
+}
+
+impl Neg for Vec3 {
+    type Output = Self;
+
+    fn neg(self) -> Self {
+        Self(-self.0, -self.1, -self.2)
+    }
+}
+
+impl Mul<i64> for Vec3 {
+    type Output = Self;
+
+    fn mul(self, k: i64) -> Self {
+        Self(self.0 * k, self.1 * k, self.2 * k)
+    }
+}
+
+impl Vec3 {
+    fn dot(&self, other: &Vec3) -> i64 {
+        self.0 * other.0 + self.1 * other.1 + self.2 * other.2
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct CubeFace {
+    pub top_left: Coords,
+    normal: Vec3,
+    right: Vec3,
+    down: Vec3,
+}
+
+impl CubeFace {
+    fn travel(&self, d: Direction) -> Vec3 {
+        match d {
+            Direction::E => self.right,
+            Direction::S => self.down,
+            Direction::W => -self.right,
+            Direction::N => -self.down,
+            _ => unreachable!(),
+        }
+    }
+
+    fn rolled(&self, d: Direction, top_left: Coords) -> CubeFace {
+        let (normal, right, down) = match d {
+            Direction::E => (self.right, -self.normal, self.down),
+            Direction::W => (-self.right, self.normal, self.down),
+            Direction::S => (self.down, self.right, -self.normal),
+            Direction::N => (-self.down, self.right, self.normal),
+            _ => unreachable!(),
+        };
+        CubeFace {
+            top_left,
+            normal,
+            right,
+            down,
+        }
+    }
+
+    fn facing(&self, v: Vec3) -> Direction {
+        if v == self.right {
+            Direction::E
+        } else if v == self.down {
+            Direction::S
+        } else if v == -self.right {
+            Direction::W
+        } else {
+            Direction::N
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct CubeNet {
+    size: i64,
+    faces: Vec<CubeFace>,
+}
+
+#[derive(Debug, Clone)]
+pub struct CubeNetError;
+
+const CARDINALS: [Direction; 4] = [Direction::E, Direction::S, Direction::W, Direction::N];
+
+impl CubeNet {
+    pub fn new<T: PartialEq>(grid: &Grid2D<T>, blank: &T) -> Result<CubeNet, CubeNetError> {
+        let cells = grid.iter().filter(|v| *v != blank).count() as i64;
+        let size = ((cells / 6) as f64).sqrt().round() as i64;
+        if size == 0 || size * size * 6 != cells {
+            return Err(CubeNetError);
+        }
+
+        // blocks of the net which are occupied by a face
+        let mut blocks = vec![];
+        for by in 0..(grid.height() / size) {
+            for bx in 0..(grid.width() / size) {
+                let top_left = Coords {
+                    x: bx * size,
+                    y: by * size,
+                };
+                if grid.at(&top_left).is_some_and(|v| v != blank) {
+                    blocks.push(top_left);
+                }
+            }
+        }
+        if blocks.len() != 6 {
+            return Err(CubeNetError);
+        }
+
+        let mut faces: Vec<Option<CubeFace>> = vec![None; 6];
+        faces[0] = Some(CubeFace {
+            top_left: blocks[0],
+            normal: Vec3(0, 0, 1),
+            right: Vec3(1, 0, 0),
+            down: Vec3(0, 1, 0),
+        });
+        let mut queue = VecDeque::from([0]);
+        while let Some(idx) = queue.pop_front() {
+            let face = faces[idx].clone().unwrap();
+            for d in CARDINALS {
+                let step = Coords::from((0, 0)).mov(d);
+                let top_left = face.top_left
+                    + Coords {
+                        x: step.x * size,
+                        y: step.y * size,
+                    };
+                if let Some(next) = blocks.iter().position(|b| *b == top_left) {
+                    if faces[next].is_none() {
+                        faces[next] = Some(face.rolled(d, top_left));
+                        queue.push_back(next);
+                    }
+                }
+            }
+        }
+
+        let faces: Vec<_> = faces
+            .into_iter()
+            .collect::<Option<_>>()
+            .ok_or(CubeNetError)?;
+        for (i, f) in faces.iter().enumerate() {
+            if faces[i + 1..].iter().any(|g| g.normal == f.normal) {
+                return Err(CubeNetError);
+            }
+        }
+
+        Ok(CubeNet { size, faces })
+    }
+
+    pub fn size(&self) -> i64 {
+        self.size
+    }
+
+    pub fn faces(&self) -> &[CubeFace] {
+        &self.faces
+    }
+
+    pub fn face_at(&self, c: &Coords) -> Option<usize> {
+        self.faces.iter().position(|f| {
+            c.x >= f.top_left.x
+                && c.y >= f.top_left.y
+                && c.x < f.top_left.x + self.size
+                && c.y < f.top_left.y + self.size
+        })
+    }
+
+    // Move one step from c into direction d (cardinal only), folding over
+    // cube edges. Returns the new coordinates and facing.
+    pub fn mov(&self, c: &Coords, d: Direction) -> (Coords, Direction) {
+        let face_idx = self.face_at(c).expect("coordinates not on cube net");
+        let next = c.mov(d);
+        if self.face_at(&next) == Some(face_idx) {
+            return (next, d);
+        }
+
+        let face = &self.faces[face_idx];
+        let local = *c - face.top_left;
+        let pos = face.normal * self.size
+            + face.right * (2 * local.x - self.size + 1)
+            + face.down * (2 * local.y - self.size + 1);
+        let travel = face.travel(d);
+        let pos_next = pos - face.normal + travel;
+
+        let face_next = self.faces.iter().find(|f| f.normal == travel).unwrap();
+        let local_next = Coords {
+            x: (pos_next.dot(&face_next.right) + self.size - 1) / 2,
+            y: (pos_next.dot(&face_next.down) + self.size - 1) / 2,
+        };
+        (
+            face_next.top_left + local_next,
+            face_next.facing(-face.normal),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn all_cells(net: &CubeNet) -> Vec<Coords> {
+        net.faces()
+            .iter()
+            .flat_map(|f| {
+                (0..net.size())
+                    .flat_map(move |y| (0..net.size()).map(move |x| f.top_left + Coords { x, y }))
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_nets() {
+        let nets = [
+            "..#.\n####\n..#.",
+            ".#..\n####\n..#.",
+            "#...\n####\n#...",
+            "#...\n####\n.#..",
+            "#...\n####\n..#.",
+            "#...\n####\n...#",
+            "##..\n.###\n..#.",
+            "##..\n.###\n...#",
+            "##..\n.###\n.#..",
+            "##..\n.##.\n..##",
+            "###..\n..###",
+        ];
+        for net_str in nets {
+            let grid = Grid2D::new(net_str).unwrap();
+            let net = CubeNet::new(&grid, &'.').unwrap();
+            assert_eq!(net.size(), 1);
+        }
+
+        let grid = Grid2D::new("###\n###").unwrap();
+        assert!(CubeNet::new(&grid, &'.').is_err());
+    }
+
+    #[test]
+    fn test_roundtrip() {
+        let rows = [
+            "..#.", //
+            "###.", //
+            "..##",
+        ];
+        let input: Vec<_> = rows
+            .iter()
+            .flat_map(|r| {
+                let line: String = r.chars().flat_map(|c| [c; 4]).collect();
+                vec![line; 4]
+            })
+            .collect();
+        let grid = Grid2D::new(&input.join("\n")).unwrap();
+        let net = CubeNet::new(&grid, &'.').unwrap();
+        assert_eq!(net.size(), 4);
+
+        // walking forth and back over any edge returns to the start
+        for c in all_cells(&net) {
+            for d in CARDINALS {
+                let (next, facing) = net.mov(&c, d);
+                assert_eq!(grid.at(&next), Some(&'#'));
+                let (back, facing_back) = net.mov(&next, facing.opposite());
+                assert_eq!((back, facing_back), (c, d.opposite()));
+            }
+        }
+
+        // walking 4 * size steps straight ahead is a full circle
+        for c in all_cells(&net) {
+            for d in CARDINALS {
+                let (mut cur, mut facing) = (c, d);
+                for _ in 0..4 * net.size() {
+                    (cur, facing) = net.mov(&cur, facing);
+                }
+                assert_eq!((cur, facing), (c, d));
+            }
+        }
+    }
+}
@@ -0,0 +1,462 @@
+use std::collections::{hash_map, HashMap, HashSet};
+use std::hash::Hash;
+use std::str::FromStr;
+
+// Expression DAG of named nodes ("wires and gates"), with memoized
+// evaluation, topological ordering and solving for a single unknown.
+
+pub trait Operator<V> {
+    fn apply(&self, args: &[V]) -> V;
+
+    // Value for args[idx] (which is None) so that apply(args) == target.
+    fn invert(&self, _target: V, _args: &[Option<V>], _idx: usize) -> Option<V> {
+        None
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Operand<K, V> {
+    Const(V),
+    Ref(K),
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Node<K, V, O> {
+    Value(V),
+    Op(O, Vec<Operand<K, V>>),
+}
+
+impl<K, V, O> Node<K, V, O> {
+    pub fn refs(&self) -> impl Iterator<Item = &K> {
+        let operands = match self {
+            Node::Value(_) => &[][..],
+            Node::Op(_, operands) => &operands[..],
+        };
+        operands.iter().filter_map(|o| match o {
+            Operand::Ref(k) => Some(k),
+            Operand::Const(_) => None,
+        })
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct Circuit<K, V, O> {
+    nodes: HashMap<K, Node<K, V, O>>,
+}
+
+impl<K, V, O> Default for Circuit<K, V, O> {
+    fn default() -> Self {
+        Circuit {
+            nodes: HashMap::new(),
+        }
+    }
+}
+
+impl<K, V, O> FromIterator<(K, Node<K, V, O>)> for Circuit<K, V, O>
+where
+    K: Eq + Hash,
+{
+    fn from_iter<I: IntoIterator<Item = (K, Node<K, V, O>)>>(iter: I) -> Self {
+        Circuit {
+            nodes: iter.into_iter().collect(),
+        }
+    }
+}
+
+impl<K, V, O> Circuit<K, V, O>
+where
+    K: Clone + Eq + Hash,
+    V: Copy,
+    O: Operator<V>,
+{
+    pub fn new() -> Circuit<K, V, O> {
+        Circuit::default()
+    }
+
+    pub fn insert(&mut self, name: K, node: Node<K, V, O>) -> Option<Node<K, V, O>> {
+        self.nodes.insert(name, node)
+    }
+
+    pub fn get(&self, name: &K) -> Option<&Node<K, V, O>> {
+        self.nodes.get(name)
+    }
+
+    pub fn iter(&self) -> hash_map::Iter<'_, K, Node<K, V, O>> {
+        self.nodes.iter()
+    }
+
+    pub fn len(&self) -> usize {
+        self.nodes.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.nodes.is_empty()
+    }
+
+    // None if a node is missing or the evaluation runs into a cycle.
+    pub fn eval(&self, name: &K) -> Option<V> {
+        self.eval_with(name, &mut HashMap::new())
+    }
+
+    // Evaluation sharing the results in memo across several calls.
+    pub fn eval_with(&self, name: &K, memo: &mut HashMap<K, V>) -> Option<V> {
+        self.eval_rec(name, memo, &mut HashSet::new())
+    }
+
+    fn eval_rec(&self, name: &K, memo: &mut HashMap<K, V>, active: &mut HashSet<K>) -> Option<V> {
+        if let Some(v) = memo.get(name) {
+            return Some(*v);
+        }
+        if !active.insert(name.clone()) {
+            return None;
+        }
+        let v = match self.nodes.get(name)? {
+            Node::Value(v) => *v,
+            Node::Op(op, operands) => {
+                let args = operands
+                    .iter()
+                    .map(|o| match o {
+                        Operand::Const(v) => Some(*v),
+                        Operand::Ref(k) => self.eval_rec(k, memo, active),
+                    })
+                    .collect::<Option<Vec<_>>>()?;
+                op.apply(&args)
+            }
+        };
+        active.remove(name);
+        memo.insert(name.clone(), v);
+        Some(v)
+    }
+
+    // All nodes, each one after the nodes it refers to. None if there is
+    // a cycle. References to missing nodes are ignored.
+    pub fn topo_order(&self) -> Option<Vec<K>> {
+        let mut order = vec![];
+        let mut done = HashSet::new();
+        let mut active = HashSet::new();
+        for name in self.nodes.keys() {
+            self.topo_visit(name, &mut order, &mut done, &mut active)?;
+        }
+        Some(order)
+    }
+
+    fn topo_visit(
+        &self,
+        name: &K,
+        order: &mut Vec<K>,
+        done: &mut HashSet<K>,
+        active: &mut HashSet<K>,
+    ) -> Option<()> {
+        if done.contains(name) {
+            return Some(());
+        }
+        if !active.insert(name.clone()) {
+            return None;
+        }
+        if let Some(node) = self.nodes.get(name) {
+            for dep in node.refs() {
+                self.topo_visit(dep, order, done, active)?;
+            }
+            order.push(name.clone());
+        }
+        active.remove(name);
+        done.insert(name.clone());
+        Some(())
+    }
+
+    pub fn depends_on(&self, name: &K, other: &K) -> bool {
+        let mut stack = vec![name];
+        let mut seen = HashSet::new();
+        while let Some(cur) = stack.pop() {
+            if cur == other {
+                return true;
+            }
+            if seen.insert(cur) {
+                if let Some(node) = self.nodes.get(cur) {
+                    stack.extend(node.refs());
+                }
+            }
+        }
+        false
+    }
+
+    // Value of the node unknown such that node name evaluates to target.
+    // The unknown must be reachable along a single path of invertible ops.
+    pub fn solve(&self, name: &K, target: V, unknown: &K) -> Option<V> {
+        let mut cur = name.clone();
+        let mut target = target;
+        while &cur != unknown {
+            match self.nodes.get(&cur)? {
+                Node::Value(_) => return None,
+                Node::Op(op, operands) => {
+                    let mut args = vec![];
+                    let mut next = None;
+                    for (idx, operand) in operands.iter().enumerate() {
+                        match operand {
+                            Operand::Ref(k) if self.depends_on(k, unknown) => {
+                                if next.is_some() {
+                                    // unknown appears more than once
+                                    return None;
+                                }
+                                next = Some((idx, k.clone()));
+                                args.push(None);
+                            }
+                            Operand::Ref(k) => args.push(Some(self.eval(k)?)),
+                            Operand::Const(v) => args.push(Some(*v)),
+                        }
+                    }
+                    let (idx, next) = next?;
+                    target = op.invert(target, &args, idx)?;
+                    cur = next;
+                }
+            }
+        }
+        Some(target)
+    }
+}
+
+// Integer arithmetic, invertible for solving
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Arith {
+    Add,
+    Sub,
+    Mul,
+    Div,
+}
+
+impl Operator<i64> for Arith {
+    fn apply(&self, args: &[i64]) -> i64 {
+        match self {
+            Arith::Add => args[0] + args[1],
+            Arith::Sub => args[0] - args[1],
+            Arith::Mul => args[0] * args[1],
+            Arith::Div => args[0] / args[1],
+        }
+    }
+
+    fn invert(&self, target: i64, args: &[Option<i64>], idx: usize) -> Option<i64> {
+        let other = args[1 - idx]?;
+        match (self, idx) {
+            (Arith::Add, _) => Some(target - other),
+            (Arith::Sub, 0) => Some(target + other),
+            (Arith::Sub, _) => Some(other - target),
+            (Arith::Mul, _) => Some(target / other),
+            (Arith::Div, 0) => Some(target * other),
+            (Arith::Div, _) => Some(other / target),
+        }
+    }
+}
+
+// Expression tree, as produced by the infix parser
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Expr<V, O> {
+    Num(V),
+    Var(String),
+    Op(O, Box<Expr<V, O>>, Box<Expr<V, O>>),
+}
+
+impl<V: Copy, O: Operator<V>> Expr<V, O> {
+    pub fn eval<F>(&self, vars: &F) -> Option<V>
+    where
+        F: Fn(&str) -> Option<V>,
+    {
+        match self {
+            Expr::Num(v) => Some(*v),
+            Expr::Var(name) => vars(name),
+            Expr::Op(op, a, b) => Some(op.apply(&[a.eval(vars)?, b.eval(vars)?])),
+        }
+    }
+}
+
+// Infix parser for binary, left-associative operators. Each operator is
+// given as (symbol, op, precedence); higher precedence binds stronger.
+pub struct InfixParser<O> {
+    ops: Vec<(String, O, usize)>,
+}
+
+enum Token<V, O> {
+    Num(V),
+    Var(String),
+    Op(O, usize),
+    ParenLeft,
+    ParenRight,
+}
+
+impl<O: Copy> InfixParser<O> {
+    pub fn new(ops: &[(&str, O, usize)]) -> InfixParser<O> {
+        let mut ops: Vec<_> = ops
+            .iter()
+            .map(|(symbol, op, prec)| (symbol.to_string(), *op, *prec))
+            .collect();
+        // longest symbol first, so that e.g. "<<" wins over "<"
+        ops.sort_by_key(|(symbol, _, _)| std::cmp::Reverse(symbol.len()));
+        InfixParser { ops }
+    }
+
+    fn tokenize<V: FromStr>(&self, s: &str) -> Result<Vec<Token<V, O>>, String> {
+        let mut tokens = vec![];
+        let mut rest = s;
+        while let Some(c) = rest.chars().next() {
+            let pos = s.len() - rest.len();
+            if c.is_whitespace() {
+                rest = &rest[c.len_utf8()..];
+            } else if c == '(' {
+                tokens.push(Token::ParenLeft);
+                rest = &rest[1..];
+            } else if c == ')' {
+                tokens.push(Token::ParenRight);
+                rest = &rest[1..];
+            } else if c.is_ascii_digit() || c.is_alphabetic() || c == '_' {
+                let len = rest
+                    .find(|c: char| !(c.is_alphanumeric() || c == '_'))
+                    .unwrap_or(rest.len());
+                let word = &rest[..len];
+                if let Some((_, op, prec)) = self.ops.iter().find(|(sym, _, _)| sym == word) {
+                    tokens.push(Token::Op(*op, *prec));
+                } else if c.is_ascii_digit() {
+                    let v = word
+                        .parse()
+                        .map_err(|_| format!("invalid number at {}: {}", pos, word))?;
+                    tokens.push(Token::Num(v));
+                } else {
+                    tokens.push(Token::Var(word.to_string()));
+                }
+                rest = &rest[len..];
+            } else if let Some((sym, op, prec)) = self
+                .ops
+                .iter()
+                .find(|(sym, _, _)| rest.starts_with(sym.as_str()))
+            {
+                tokens.push(Token::Op(*op, *prec));
+                rest = &rest[sym.len()..];
+            } else {
+                return Err(format!("unexpected character at {}: {}", pos, c));
+            }
+        }
+        Ok(tokens)
+    }
+
+    pub fn parse<V: FromStr>(&self, s: &str) -> Result<Expr<V, O>, String> {
+        fn reduce<V, O>(output: &mut Vec<Expr<V, O>>, op: O) -> Result<(), String> {
+            let b = output.pop().ok_or("missing operand")?;
+            let a = output.pop().ok_or("missing operand")?;
+            output.push(Expr::Op(op, Box::new(a), Box::new(b)));
+            Ok(())
+        }
+
+        let mut output = vec![];
+        // None marks an opening parenthesis
+        let mut op_stack: Vec<Option<(O, usize)>> = vec![];
+
+        for token in self.tokenize(s)? {
+            match token {
+                Token::Num(v) => output.push(Expr::Num(v)),
+                Token::Var(name) => output.push(Expr::Var(name)),
+                Token::Op(op, prec) => {
+                    while let Some(Some((top_op, top_prec))) = op_stack.last() {
+                        if *top_prec < prec {
+                            break;
+                        }
+                        reduce(&mut output, *top_op)?;
+                        op_stack.pop();
+                    }
+                    op_stack.push(Some((op, prec)));
+                }
+                Token::ParenLeft => op_stack.push(None),
+                Token::ParenRight => loop {
+                    match op_stack.pop() {
+                        Some(Some((op, _))) => reduce(&mut output, op)?,
+                        Some(None) => break,
+                        None => return Err(String::from("unbalanced ')'")),
+                    }
+                },
+            }
+        }
+        while let Some(top) = op_stack.pop() {
+            match top {
+                Some((op, _)) => reduce(&mut output, op)?,
+                None => return Err(String::from("unbalanced '('")),
+            }
+        }
+
+        match (output.pop(), output.is_empty()) {
+            (Some(expr), true) => Ok(expr),
+            (None, _) => Err(String::from("empty expression")),
+            _ => Err(String::from("missing operator")),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn arith_circuit() -> Circuit<&'static str, i64, Arith> {
+        // root = (x + 3) * (y - 1)
+        let mut c = Circuit::new();
+        c.insert("x", Node::Value(4));
+        c.insert("y", Node::Value(5));
+        c.insert(
+            "a",
+            Node::Op(Arith::Add, vec![Operand::Ref("x"), Operand::Const(3)]),
+        );
+        c.insert(
+            "b",
+            Node::Op(Arith::Sub, vec![Operand::Ref("y"), Operand::Const(1)]),
+        );
+        c.insert(
+            "root",
+            Node::Op(Arith::Mul, vec![Operand::Ref("a"), Operand::Ref("b")]),
+        );
+        c
+    }
+
+    #[test]
+    fn test_eval() {
+        let c = arith_circuit();
+        assert_eq!(c.eval(&"root"), Some(28));
+        assert_eq!(c.eval(&"missing"), None);
+
+        let order = c.topo_order().unwrap();
+        let pos = |k| order.iter().position(|o| *o == k).unwrap();
+        assert!(pos("x") < pos("a"));
+        assert!(pos("a") < pos("root"));
+        assert!(pos("b") < pos("root"));
+    }
+
+    #[test]
+    fn test_cycle() {
+        let mut c = arith_circuit();
+        c.insert(
+            "x",
+            Node::Op(Arith::Add, vec![Operand::Ref("root"), Operand::Const(1)]),
+        );
+        assert_eq!(c.eval(&"root"), None);
+        assert_eq!(c.topo_order(), None);
+    }
+
+    #[test]
+    fn test_solve() {
+        let c = arith_circuit();
+        assert!(c.depends_on(&"root", &"y"));
+        assert!(!c.depends_on(&"a", &"y"));
+        assert_eq!(c.solve(&"root", 56, &"x"), Some(11));
+        assert_eq!(c.solve(&"root", 56, &"y"), Some(9));
+    }
+
+    #[test]
+    fn test_infix() {
+        let parser = InfixParser::new(&[
+            ("+", Arith::Add, 1),
+            ("-", Arith::Sub, 1),
+            ("*", Arith::Mul, 2),
+            ("/", Arith::Div, 2),
+        ]);
+        let expr = parser.parse::<i64>("2 + 3 * (4 - x) / 2").unwrap();
+        assert_eq!(expr.eval(&|v| (v == "x").then_some(2)), Some(5));
+        assert_eq!(expr.eval(&|_| None), None);
+        assert!(parser.parse::<i64>("(1 + 2").is_err());
+        assert!(parser.parse::<i64>("1 + ").is_err());
+        assert!(parser.parse::<i64>("1 % 2").is_err());
+    }
+}
@@ -1,3 +1,4 @@
+use crate::cubenet::CubeNet;
 use itertools::concat;
 use std::cmp::Ordering;
 use std::fmt::{self, Display, Formatter};
@@ -20,6 +21,7 @@ pub enum Wrap {
     WrapX,
     WrapY,
     WrapXY,
+    Cube(CubeNet),
 }
 
 #[allow(dead_code)]
@@ -659,7 +661,7 @@ impl<'a, T> Iterator for TraverseIter<'a, T> {
 
     fn next(&mut self) -> Option<&'a T> {
         let ret = self.grid.at(&self.cur);
-        match self.wrap {
+        match &self.wrap {
             Wrap::None => {
                 self.cur.x += self.d.x;
                 self.cur.y += self.d.y;
@@ -676,6 +678,18 @@ impl<'a, T> Iterator for TraverseIter<'a, T> {
                 self.cur.x = (self.cur.x + self.d.x) % self.grid.width();
                 self.cur.y = (self.cur.y + self.d.y) % self.grid.height();
             }
+            Wrap::Cube(net) => {
+                let d = match (self.d.x, self.d.y) {
+                    (1, 0) => Direction::E,
+                    (0, 1) => Direction::S,
+                    (-1, 0) => Direction::W,
+                    (0, -1) => Direction::N,
+                    _ => panic!("cube traversal needs a cardinal unit step: {}", self.d),
+                };
+                let (cur, d) = net.mov(&self.cur, d);
+                self.cur = cur;
+                self.d = Coords::from((0, 0)).mov(d);
+            }
         }
         ret
     }
@@ -780,6 +794,37 @@ mnop"#,
         assert_eq!(format!("{}", clip), "fg\njk");
     }
 
+    #[test]
+    fn test_traverse_cube() {
+        let example = Grid2D::new(
+            r#"..a.
+bcde
+..f."#,
+        )
+        .unwrap();
+        let net = CubeNet::new(&example, &'.').unwrap();
+        let s: String = example
+            .traverse_init_wrap(
+                &Coords { x: 0, y: 1 },
+                &Coords { x: 1, y: 0 },
+                Wrap::Cube(net),
+            )
+            .take(8)
+            .collect();
+        assert_eq!(s, "bcdebcde");
+
+        let net = CubeNet::new(&example, &'.').unwrap();
+        let s: String = example
+            .traverse_init_wrap(
+                &Coords { x: 2, y: 0 },
+                &Coords { x: 1, y: 0 },
+                Wrap::Cube(net),
+            )
+            .take(4)
+            .collect();
+        assert_eq!(s, "aefc");
+    }
+
     #[test]
     fn test_paste() {
         let mut example = Grid2D::new(
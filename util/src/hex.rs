@@ -1,10 +1,23 @@
-use std::ops::{Add, AddAssign};
+use std::collections::hash_map;
+use std::collections::HashMap;
+use std::ops::{Add, AddAssign, Mul, Sub, SubAssign};
 
 // Hex grid with axial coordinates
 
-#[derive(Copy, Clone, Eq, PartialEq, Hash)]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
 pub struct HexCoord(pub i32, pub i32);
 
+// Axial directions, in pointy layout: e, se, sw, w, nw, ne
+// (in flat layout: se, s, sw, nw, n, ne)
+pub const DIRECTIONS: [HexCoord; 6] = [
+    HexCoord(1, 0),
+    HexCoord(0, 1),
+    HexCoord(-1, 1),
+    HexCoord(-1, 0),
+    HexCoord(0, -1),
+    HexCoord(1, -1),
+];
+
 impl Add for HexCoord {
     type Output = Self;
 
@@ -19,6 +32,28 @@ impl AddAssign for HexCoord {
     }
 }
 
+impl Sub for HexCoord {
+    type Output = Self;
+
+    fn sub(self, other: Self) -> Self {
+        Self(self.0 - other.0, self.1 - other.1)
+    }
+}
+
+impl SubAssign for HexCoord {
+    fn sub_assign(&mut self, other: Self) {
+        *self = Self(self.0 - other.0, self.1 - other.1);
+    }
+}
+
+impl Mul<i32> for HexCoord {
+    type Output = Self;
+
+    fn mul(self, k: i32) -> Self {
+        Self(self.0 * k, self.1 * k)
+    }
+}
+
 impl HexCoord {
     pub fn direction_pointy(dir_str: &str) -> Option<HexCoord> {
         match dir_str {
@@ -50,4 +85,310 @@ impl HexCoord {
             + (self.1 - other.1).unsigned_abs())
             / 2
     }
+
+    pub fn neighbors(&self) -> [HexCoord; 6] {
+        DIRECTIONS.map(|d| *self + d)
+    }
+
+    // All hexes at exactly the given distance, walking around clockwise
+    // (pointy layout), starting at the east corner.
+    pub fn ring(&self, radius: u32) -> Vec<HexCoord> {
+        if radius == 0 {
+            return vec![*self];
+        }
+        let mut ring = vec![];
+        let mut cur = *self + DIRECTIONS[0] * radius as i32;
+        for i in 0..6 {
+            for _ in 0..radius {
+                ring.push(cur);
+                cur += DIRECTIONS[(i + 2) % 6];
+            }
+        }
+        ring
+    }
+
+    // All hexes up to the given distance, ring by ring from the center.
+    pub fn spiral(&self, radius: u32) -> Vec<HexCoord> {
+        (0..=radius).flat_map(|r| self.ring(r)).collect()
+    }
+
+    // All hexes on the straight line between self and other (both included).
+    pub fn line(&self, other: &HexCoord) -> Vec<HexCoord> {
+        let n = self.dist(other);
+        if n == 0 {
+            return vec![*self];
+        }
+        let a = CubeCoord::from(*self);
+        let b = CubeCoord::from(*other);
+        // nudge to avoid rounding ties exactly on hex edges
+        let (aq, ar) = (a.q as f64 + 1e-6, a.r as f64 + 1e-6);
+        let (bq, br) = (b.q as f64 + 1e-6, b.r as f64 + 1e-6);
+        (0..=n)
+            .map(|i| {
+                let t = i as f64 / n as f64;
+                let q = aq + (bq - aq) * t;
+                let r = ar + (br - ar) * t;
+                CubeCoord::round(q, r, -q - r).into()
+            })
+            .collect()
+    }
+
+    // Rotation by 60 degrees around center, clockwise in pointy layout.
+    pub fn rotate_right(&self, center: &HexCoord) -> HexCoord {
+        let v = CubeCoord::from(*self - *center);
+        *center + HexCoord::from(CubeCoord::new(-v.r, -v.s))
+    }
+
+    // Rotation by 60 degrees around center, counter-clockwise in pointy layout.
+    pub fn rotate_left(&self, center: &HexCoord) -> HexCoord {
+        let v = CubeCoord::from(*self - *center);
+        *center + HexCoord::from(CubeCoord::new(-v.s, -v.q))
+    }
+
+    pub fn to_offset(&self, layout: OffsetLayout) -> (i32, i32) {
+        let (q, r) = (self.0, self.1);
+        match layout {
+            OffsetLayout::OddR => (q + (r - (r & 1)) / 2, r),
+            OffsetLayout::EvenR => (q + (r + (r & 1)) / 2, r),
+            OffsetLayout::OddQ => (q, r + (q - (q & 1)) / 2),
+            OffsetLayout::EvenQ => (q, r + (q + (q & 1)) / 2),
+        }
+    }
+
+    pub fn from_offset(col: i32, row: i32, layout: OffsetLayout) -> HexCoord {
+        match layout {
+            OffsetLayout::OddR => HexCoord(col - (row - (row & 1)) / 2, row),
+            OffsetLayout::EvenR => HexCoord(col - (row + (row & 1)) / 2, row),
+            OffsetLayout::OddQ => HexCoord(col, row - (col - (col & 1)) / 2),
+            OffsetLayout::EvenQ => HexCoord(col, row - (col + (col & 1)) / 2),
+        }
+    }
+}
+
+// Offset coordinates (col, row): every odd / even row (pointy) or column
+// (flat) is shoved by half a hex.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum OffsetLayout {
+    OddR,
+    EvenR,
+    OddQ,
+    EvenQ,
+}
+
+// Cube coordinates, with the invariant q + r + s == 0
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub struct CubeCoord {
+    pub q: i32,
+    pub r: i32,
+    pub s: i32,
+}
+
+impl CubeCoord {
+    pub fn new(q: i32, r: i32) -> CubeCoord {
+        CubeCoord { q, r, s: -q - r }
+    }
+
+    fn round(q: f64, r: f64, s: f64) -> CubeCoord {
+        let (mut rq, mut rr, rs) = (q.round(), r.round(), s.round());
+        let (dq, dr, ds) = ((rq - q).abs(), (rr - r).abs(), (rs - s).abs());
+        if dq > dr && dq > ds {
+            rq = -rr - rs;
+        } else if dr > ds {
+            rr = -rq - rs;
+        }
+        CubeCoord::new(rq as i32, rr as i32)
+    }
+}
+
+impl From<HexCoord> for CubeCoord {
+    fn from(h: HexCoord) -> Self {
+        CubeCoord::new(h.0, h.1)
+    }
+}
+
+impl From<CubeCoord> for HexCoord {
+    fn from(c: CubeCoord) -> Self {
+        HexCoord(c.q, c.r)
+    }
+}
+
+// Sparse storage of values on a hex grid
+#[derive(Clone, Debug, Default)]
+pub struct HexGrid<T> {
+    cells: HashMap<HexCoord, T>,
+}
+
+impl<T> HexGrid<T> {
+    pub fn new() -> HexGrid<T> {
+        HexGrid {
+            cells: HashMap::new(),
+        }
+    }
+
+    pub fn at(&self, c: &HexCoord) -> Option<&T> {
+        self.cells.get(c)
+    }
+
+    pub fn at_mut(&mut self, c: &HexCoord) -> Option<&mut T> {
+        self.cells.get_mut(c)
+    }
+
+    pub fn set(&mut self, c: HexCoord, v: T) -> Option<T> {
+        self.cells.insert(c, v)
+    }
+
+    pub fn remove(&mut self, c: &HexCoord) -> Option<T> {
+        self.cells.remove(c)
+    }
+
+    pub fn contains(&self, c: &HexCoord) -> bool {
+        self.cells.contains_key(c)
+    }
+
+    pub fn len(&self) -> usize {
+        self.cells.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.cells.is_empty()
+    }
+
+    pub fn iter(&self) -> hash_map::Iter<'_, HexCoord, T> {
+        self.cells.iter()
+    }
+
+    pub fn coords(&self) -> hash_map::Keys<'_, HexCoord, T> {
+        self.cells.keys()
+    }
+
+    pub fn neighbors(&self, c: &HexCoord) -> [Option<&T>; 6] {
+        c.neighbors().map(|n| self.cells.get(&n))
+    }
+
+    pub fn count_neighbors<F>(&self, c: &HexCoord, pred: F) -> usize
+    where
+        F: Fn(&T) -> bool,
+    {
+        self.neighbors(c)
+            .iter()
+            .filter(|v| v.is_some_and(&pred))
+            .count()
+    }
+
+    // Text rendering in pointy layout: every row is shifted by half a hex
+    // against the previous one, cells are two characters apart.
+    pub fn render<F>(&self, to_char: F) -> String
+    where
+        F: Fn(Option<&T>) -> char,
+    {
+        if self.cells.is_empty() {
+            return String::new();
+        }
+        let col = |c: &HexCoord| 2 * c.0 + c.1;
+        let min_col = self.cells.keys().map(col).min().unwrap();
+        let max_col = self.cells.keys().map(col).max().unwrap();
+        let min_row = self.cells.keys().map(|c| c.1).min().unwrap();
+        let max_row = self.cells.keys().map(|c| c.1).max().unwrap();
+
+        let mut lines = vec![];
+        for r in min_row..=max_row {
+            let mut line = String::new();
+            for x in min_col..=max_col {
+                if (x - r).rem_euclid(2) == 0 {
+                    let c = HexCoord((x - r) / 2, r);
+                    line.push(to_char(self.cells.get(&c)));
+                } else {
+                    line.push(' ');
+                }
+            }
+            lines.push(line.trim_end().to_string());
+        }
+        lines.join("\n")
+    }
+}
+
+impl<T> FromIterator<(HexCoord, T)> for HexGrid<T> {
+    fn from_iter<I: IntoIterator<Item = (HexCoord, T)>>(iter: I) -> Self {
+        HexGrid {
+            cells: iter.into_iter().collect(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ring_spiral() {
+        let center = HexCoord(2, -1);
+        assert_eq!(center.ring(0), vec![center]);
+        for radius in 1..5 {
+            let ring = center.ring(radius);
+            assert_eq!(ring.len(), 6 * radius as usize);
+            assert!(ring.iter().all(|h| h.dist(&center) == radius));
+        }
+        assert_eq!(center.spiral(3).len(), 1 + 6 + 12 + 18);
+    }
+
+    #[test]
+    fn test_line() {
+        let a = HexCoord(0, 0);
+        let b = HexCoord(3, -1);
+        let line = a.line(&b);
+        assert_eq!(line.len(), 4);
+        assert_eq!(line[0], a);
+        assert_eq!(line[3], b);
+        for w in line.windows(2) {
+            assert_eq!(w[0].dist(&w[1]), 1);
+        }
+    }
+
+    #[test]
+    fn test_rotate() {
+        let center = HexCoord(1, 1);
+        let h = HexCoord(3, 0);
+        assert_eq!(HexCoord(1, 0).rotate_right(&HexCoord(0, 0)), HexCoord(0, 1));
+        let mut r = h;
+        for _ in 0..6 {
+            r = r.rotate_right(&center);
+            assert_eq!(r.dist(&center), h.dist(&center));
+            assert_eq!(r.rotate_left(&center).rotate_right(&center), r);
+        }
+        assert_eq!(r, h);
+    }
+
+    #[test]
+    fn test_offset() {
+        let layouts = [
+            OffsetLayout::OddR,
+            OffsetLayout::EvenR,
+            OffsetLayout::OddQ,
+            OffsetLayout::EvenQ,
+        ];
+        for h in HexCoord(0, 0).spiral(4) {
+            for layout in layouts {
+                let (col, row) = h.to_offset(layout);
+                assert_eq!(HexCoord::from_offset(col, row, layout), h);
+            }
+        }
+        assert_eq!(HexCoord(-1, 1).to_offset(OffsetLayout::OddR), (-1, 1));
+        assert_eq!(CubeCoord::from(HexCoord(2, -3)).s, 1);
+    }
+
+    #[test]
+    fn test_render() {
+        let grid: HexGrid<bool> = HexCoord(0, 0)
+            .spiral(1)
+            .into_iter()
+            .map(|h| (h, h == HexCoord(0, 0)))
+            .collect();
+        assert_eq!(grid.count_neighbors(&HexCoord(1, 0), |v| !*v), 2);
+        let s = grid.render(|v| match v {
+            Some(true) => '#',
+            Some(false) => 'o',
+            None => '.',
+        });
+        assert_eq!(s, " o o\no # o\n o o");
+    }
 }
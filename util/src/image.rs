@@ -0,0 +1,243 @@
+use crate::grid2d::Grid2D;
+use std::path::Path;
+
+// Image export of grids: PPM (plain or binary) and PNG. The PNG encoder
+// needs no dependencies because it only uses uncompressed deflate blocks.
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum ImageFormat {
+    PpmPlain,
+    Ppm,
+    Png,
+}
+
+impl ImageFormat {
+    pub fn from_path(path: &Path) -> Option<ImageFormat> {
+        match path.extension()?.to_str()? {
+            "ppm" => Some(ImageFormat::Ppm),
+            "png" => Some(ImageFormat::Png),
+            _ => None,
+        }
+    }
+}
+
+pub type Rgb = [u8; 3];
+
+impl<T> Grid2D<T> {
+    pub fn to_image<F>(&self, format: ImageFormat, scale: usize, color: F) -> Vec<u8>
+    where
+        F: Fn(&T) -> Rgb,
+    {
+        let scale = scale.max(1);
+        let width = self.width() as usize * scale;
+        let height = self.height() as usize * scale;
+
+        let mut rows = Vec::with_capacity(height);
+        for y in 0..self.height() {
+            let mut row = Vec::with_capacity(width * 3);
+            for v in self.row(y).unwrap() {
+                let rgb = color(v);
+                for _ in 0..scale {
+                    row.extend(rgb);
+                }
+            }
+            for _ in 0..scale {
+                rows.push(row.clone());
+            }
+        }
+
+        match format {
+            ImageFormat::PpmPlain => {
+                let mut s = format!("P3\n{} {}\n255\n", width, height);
+                for row in rows {
+                    let vals: Vec<_> = row.iter().map(|v| v.to_string()).collect();
+                    s += &vals.join(" ");
+                    s += "\n";
+                }
+                s.into_bytes()
+            }
+            ImageFormat::Ppm => {
+                let mut data = format!("P6\n{} {}\n255\n", width, height).into_bytes();
+                for row in rows {
+                    data.extend(row);
+                }
+                data
+            }
+            ImageFormat::Png => png(width, height, &rows),
+        }
+    }
+
+    // The format is chosen by the file extension (.ppm or .png).
+    pub fn save_image<F>(&self, path: &Path, scale: usize, color: F) -> std::io::Result<()>
+    where
+        F: Fn(&T) -> Rgb,
+    {
+        let format = ImageFormat::from_path(path).ok_or_else(|| {
+            std::io::Error::new(
+                std::io::ErrorKind::InvalidInput,
+                format!("unknown image format: {}", path.display()),
+            )
+        })?;
+        std::fs::write(path, self.to_image(format, scale, color))
+    }
+}
+
+impl Grid2D<usize> {
+    // Color mapping for distance maps: blue (near) to red (far).
+    // usize::MAX is taken as "unreachable" and drawn black.
+    pub fn heatmap(&self) -> impl Fn(&usize) -> Rgb {
+        let reachable = || self.iter().filter(|v| **v != usize::MAX);
+        let min = reachable().min().copied().unwrap_or(0);
+        let max = reachable().max().copied().unwrap_or(0);
+        move |v| {
+            if *v == usize::MAX {
+                [0, 0, 0]
+            } else if max == min {
+                heat_color(0.0)
+            } else {
+                heat_color((*v - min) as f64 / (max - min) as f64)
+            }
+        }
+    }
+}
+
+fn heat_color(t: f64) -> Rgb {
+    // blue -> cyan -> green -> yellow -> red
+    const STOPS: [Rgb; 5] = [
+        [0, 0, 255],
+        [0, 255, 255],
+        [0, 255, 0],
+        [255, 255, 0],
+        [255, 0, 0],
+    ];
+    let pos = t.clamp(0.0, 1.0) * (STOPS.len() - 1) as f64;
+    let idx = (pos as usize).min(STOPS.len() - 2);
+    let frac = pos - idx as f64;
+    let (a, b) = (STOPS[idx], STOPS[idx + 1]);
+    [0, 1, 2].map(|i| (a[i] as f64 + (b[i] as f64 - a[i] as f64) * frac).round() as u8)
+}
+
+fn png(width: usize, height: usize, rows: &[Vec<u8>]) -> Vec<u8> {
+    let mut data = vec![0x89, b'P', b'N', b'G', 0x0d, 0x0a, 0x1a, 0x0a];
+
+    let mut ihdr = vec![];
+    ihdr.extend((width as u32).to_be_bytes());
+    ihdr.extend((height as u32).to_be_bytes());
+    // 8 bit RGB, no interlacing
+    ihdr.extend([8, 2, 0, 0, 0]);
+    png_chunk(&mut data, b"IHDR", &ihdr);
+
+    let mut raw = vec![];
+    for row in rows {
+        // filter type: none
+        raw.push(0);
+        raw.extend(row);
+    }
+    png_chunk(&mut data, b"IDAT", &zlib_stored(&raw));
+    png_chunk(&mut data, b"IEND", &[]);
+    data
+}
+
+fn png_chunk(data: &mut Vec<u8>, kind: &[u8; 4], content: &[u8]) {
+    data.extend((content.len() as u32).to_be_bytes());
+    let mut crc_data = kind.to_vec();
+    crc_data.extend(content);
+    data.extend(&crc_data);
+    data.extend(crc32(&crc_data).to_be_bytes());
+}
+
+fn zlib_stored(raw: &[u8]) -> Vec<u8> {
+    let mut out = vec![0x78, 0x01];
+    let blocks: Vec<_> = raw.chunks(0xffff).collect();
+    if blocks.is_empty() {
+        out.extend([1, 0, 0, 0xff, 0xff]);
+    }
+    for (i, block) in blocks.iter().enumerate() {
+        out.push(u8::from(i == blocks.len() - 1));
+        let len = block.len() as u16;
+        out.extend(len.to_le_bytes());
+        out.extend((!len).to_le_bytes());
+        out.extend(*block);
+    }
+    out.extend(adler32(raw).to_be_bytes());
+    out
+}
+
+fn crc32(data: &[u8]) -> u32 {
+    let mut crc = 0xffffffff_u32;
+    for b in data {
+        crc ^= *b as u32;
+        for _ in 0..8 {
+            crc = if crc & 1 != 0 {
+                (crc >> 1) ^ 0xedb88320
+            } else {
+                crc >> 1
+            };
+        }
+    }
+    !crc
+}
+
+fn adler32(data: &[u8]) -> u32 {
+    let (mut a, mut b) = (1_u32, 0_u32);
+    for chunk in data.chunks(5552) {
+        for v in chunk {
+            a += *v as u32;
+            b += a;
+        }
+        a %= 65521;
+        b %= 65521;
+    }
+    (b << 16) | a
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_checksums() {
+        assert_eq!(crc32(b"123456789"), 0xcbf43926);
+        assert_eq!(crc32(b"IEND"), 0xae426082);
+        assert_eq!(adler32(b"Wikipedia"), 0x11e60398);
+    }
+
+    #[test]
+    fn test_ppm_plain() {
+        let grid = Grid2D::new("#.").unwrap();
+        let color = |c: &char| if *c == '#' { [255, 0, 0] } else { [0, 0, 0] };
+        let data = grid.to_image(ImageFormat::PpmPlain, 1, color);
+        assert_eq!(
+            String::from_utf8(data).unwrap(),
+            "P3\n2 1\n255\n255 0 0 0 0 0\n"
+        );
+        let data = grid.to_image(ImageFormat::Ppm, 2, color);
+        assert_eq!(data.len(), "P6\n4 2\n255\n".len() + 4 * 2 * 3);
+    }
+
+    #[test]
+    fn test_png() {
+        let grid = Grid2D::new("ab\ncd").unwrap();
+        let data = grid.to_image(ImageFormat::Png, 3, |_| [1, 2, 3]);
+        assert_eq!(
+            &data[..8],
+            &[0x89, b'P', b'N', b'G', 0x0d, 0x0a, 0x1a, 0x0a]
+        );
+        assert_eq!(&data[16..24], &[0, 0, 0, 6, 0, 0, 0, 6]);
+        assert_eq!(
+            &data[data.len() - 12..],
+            &[0, 0, 0, 0, b'I', b'E', b'N', b'D', 0xae, 0x42, 0x60, 0x82]
+        );
+    }
+
+    #[test]
+    fn test_heatmap() {
+        let mut grid = Grid2D::with_default((3, 1).into(), &0_usize);
+        grid.set(&(1, 0).into(), 10);
+        grid.set(&(2, 0).into(), usize::MAX);
+        let color = grid.heatmap();
+        assert_eq!(color(&0), [0, 0, 255]);
+        assert_eq!(color(&10), [255, 0, 0]);
+        assert_eq!(color(&usize::MAX), [0, 0, 0]);
+    }
+}
@@ -1,3 +1,4 @@
+use crate::cancel;
 use std::collections::{HashMap, VecDeque};
 
 pub type IntSize = i64;
@@ -140,6 +141,7 @@ impl Intcode {
     pub fn run(&mut self) {
         self.state = RunState::Running;
         while self.state == RunState::Running {
+            cancel::check();
             if let Err(e) = self.op() {
                 println!("Intcode Error: {}", e);
                 self.state = RunState::Error;
@@ -1,10 +1,59 @@
+use crate::grid2d::{Coords, Grid2D};
 use std::fmt;
 
+// Knot hash (2017 day 10) with configurable parameters. The default
+// parameters give the full hash of part 2, Params::single_round the plain
+// knot of part 1.
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Params {
+    pub size: usize,
+    pub rounds: usize,
+    // lengths appended to the input
+    pub suffix: Vec<usize>,
+    // number of elements xored into one value of the dense hash
+    pub block_size: usize,
+}
+
+impl Default for Params {
+    fn default() -> Self {
+        Params {
+            size: 256,
+            rounds: 64,
+            suffix: vec![17, 31, 73, 47, 23],
+            block_size: 16,
+        }
+    }
+}
+
+impl Params {
+    pub fn single_round(size: usize) -> Params {
+        Params {
+            size,
+            rounds: 1,
+            suffix: vec![],
+            block_size: 1,
+        }
+    }
+}
+
+// Collects the input lengths; every round runs over all of them, so the
+// hash is only computed by finish(). Hashing can go on after finish().
+#[derive(Clone, Debug)]
+pub struct KnotHasher {
+    params: Params,
+    lengths: Vec<usize>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
 pub struct KnotHash {
-    v: [u8; 16],
+    sparse: Vec<usize>,
+    dense: Vec<usize>,
+    // bits per value of the dense hash
+    width: u32,
 }
 
-pub fn knot(v: &mut [u8], lengths: &[usize], mut cur: usize, mut skip: usize) -> (usize, usize) {
+pub fn knot<T>(v: &mut [T], lengths: &[usize], mut cur: usize, mut skip: usize) -> (usize, usize) {
     let vlen = v.len();
     for length in lengths {
         if *length > 1 {
@@ -22,63 +71,156 @@ pub fn knot(v: &mut [u8], lengths: &[usize], mut cur: usize, mut skip: usize) ->
     (cur, skip)
 }
 
-impl KnotHash {
-    pub fn from(s: &str) -> KnotHash {
-        let mut lengths: Vec<_> = s.chars().map(|c| c as u8 as usize).collect();
-        let add = vec![17, 31, 73, 47, 23];
-        lengths.extend(add);
-        let mut v: Vec<_> = (0..=255).collect();
-
-        let mut cur = 0;
-        let mut skip = 0;
-        for _ in 0..64 {
-            let res = knot(&mut v, &lengths, cur, skip);
-            cur = res.0;
-            skip = res.1;
+impl KnotHasher {
+    pub fn new(params: Params) -> KnotHasher {
+        KnotHasher {
+            params,
+            lengths: vec![],
+        }
+    }
+
+    // Input bytes are taken as lengths.
+    pub fn update(&mut self, data: &[u8]) -> &mut Self {
+        self.lengths.extend(data.iter().map(|b| *b as usize));
+        self
+    }
+
+    pub fn update_lengths(&mut self, lengths: &[usize]) -> &mut Self {
+        self.lengths.extend(lengths);
+        self
+    }
+
+    pub fn finish(&self) -> KnotHash {
+        let params = &self.params;
+        let mut lengths = self.lengths.clone();
+        lengths.extend(&params.suffix);
+
+        let mut v: Vec<usize> = (0..params.size).collect();
+        let (mut cur, mut skip) = (0, 0);
+        if params.size > 0 {
+            for _ in 0..params.rounds {
+                (cur, skip) = knot(&mut v, &lengths, cur, skip);
+            }
         }
-        let dense: Vec<u8> = (0..16)
-            .map(|i| {
-                let bas = 16 * i;
-                (1..16).fold(v[bas], |acc, x| acc ^ v[bas + x])
-            })
+        let dense = v
+            .chunks(params.block_size.max(1))
+            .map(|block| block.iter().fold(0, |acc, x| acc ^ x))
             .collect();
         KnotHash {
-            v: [
-                dense[0], dense[1], dense[2], dense[3], dense[4], dense[5], dense[6], dense[7],
-                dense[8], dense[9], dense[10], dense[11], dense[12], dense[13], dense[14],
-                dense[15],
-            ],
+            sparse: v,
+            dense,
+            width: usize::BITS - params.size.saturating_sub(1).leading_zeros(),
         }
     }
+}
+
+impl KnotHash {
+    pub fn from(s: &str) -> KnotHash {
+        KnotHasher::new(Params::default())
+            .update(s.as_bytes())
+            .finish()
+    }
 
-    pub fn bytes(&self) -> impl Iterator<Item = &u8> {
-        vec![
-            &self.v[0],
-            &self.v[1],
-            &self.v[2],
-            &self.v[3],
-            &self.v[4],
-            &self.v[5],
-            &self.v[6],
-            &self.v[7],
-            &self.v[8],
-            &self.v[9],
-            &self.v[10],
-            &self.v[11],
-            &self.v[12],
-            &self.v[13],
-            &self.v[14],
-            &self.v[15],
-        ]
-        .into_iter()
+    // the list after all rounds
+    pub fn sparse(&self) -> &[usize] {
+        &self.sparse
+    }
+
+    pub fn dense(&self) -> &[usize] {
+        &self.dense
+    }
+
+    // Bits of the dense hash, most significant first.
+    pub fn bits(&self) -> impl Iterator<Item = bool> + '_ {
+        self.dense
+            .iter()
+            .flat_map(move |v| (0..self.width).rev().map(move |i| v & (1 << i) != 0))
+    }
+
+    pub fn count_ones(&self) -> u32 {
+        self.dense.iter().map(|v| v.count_ones()).sum()
     }
 }
 
+// One hash per row, as in the disk of 2017 day 14.
+pub fn bit_grid(hashes: &[KnotHash]) -> Grid2D<bool> {
+    let width = hashes.iter().map(|h| h.bits().count()).max().unwrap_or(0);
+    let mut grid = Grid2D::with_default(
+        Coords {
+            x: width as i64,
+            y: hashes.len() as i64,
+        },
+        &false,
+    );
+    for (y, hash) in hashes.iter().enumerate() {
+        for (x, bit) in hash.bits().enumerate() {
+            grid.set(
+                &Coords {
+                    x: x as i64,
+                    y: y as i64,
+                },
+                bit,
+            );
+        }
+    }
+    grid
+}
+
 impl fmt::Display for KnotHash {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        for x in self.v {
-            write!(f, "{:02x}", x)?;
+        let digits = self.width.div_ceil(4) as usize;
+        for x in &self.dense {
+            write!(f, "{:0digits$x}", x)?;
         }
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_default() {
+        assert_eq!(
+            KnotHash::from("").to_string(),
+            "a2582a3a0e66e6e86e3812dcb672a272"
+        );
+
+        let mut hasher = KnotHasher::new(Params::default());
+        hasher.update(b"AoC");
+        hasher.update(b" 2017");
+        assert_eq!(hasher.finish(), KnotHash::from("AoC 2017"));
+        assert_eq!(
+            hasher.finish().to_string(),
+            "33efeb34ea91902bb2f59c9920caa6cd"
+        );
+    }
+
+    #[test]
+    fn test_single_round() {
+        let hash = KnotHasher::new(Params::single_round(5))
+            .update_lengths(&[3, 4, 1, 5])
+            .finish();
+        assert_eq!(hash.sparse(), &[3, 4, 2, 1, 0]);
+        assert_eq!(hash.dense(), hash.sparse());
+        assert_eq!(hash.to_string(), "34210");
+    }
+
+    #[test]
+    fn test_bits() {
+        let hash = KnotHash::from("flqrgnkx-0");
+        let row: String = hash
+            .bits()
+            .take(8)
+            .map(|b| if b { '#' } else { '.' })
+            .collect();
+        assert_eq!(row, "##.#.#..");
+        assert_eq!(hash.count_ones(), hash.bits().filter(|b| *b).count() as u32);
+
+        let grid = bit_grid(&[hash.clone(), KnotHash::from("flqrgnkx-1")]);
+        assert_eq!(grid.dimensions(), Coords { x: 128, y: 2 });
+        let row: Vec<_> = grid.row(1).unwrap().into_iter().take(4).copied().collect();
+        assert_eq!(row, vec![false, true, false, true]);
+    }
+}
@@ -1,9 +1,19 @@
+pub mod asmparse;
 pub mod assembunny;
+pub mod cancel;
 pub mod chardistrib;
 pub mod combos;
+pub mod counter;
+pub mod cubenet;
+pub mod expr;
 pub mod gcd;
 pub mod grid2d;
 pub mod hex;
+pub mod image;
 pub mod intcode;
 pub mod knothash;
+pub mod md5search;
+pub mod opinfer;
+pub mod viz;
 pub mod wrist;
+pub mod wristlift;
@@ -0,0 +1,280 @@
+use crate::cancel;
+use md5::{Digest as _, Md5};
+use std::collections::VecDeque;
+use std::thread;
+
+// MD5 brute-force searches as in 2015 day 4, 2016 day 5, 14 and 17: the
+// hash of a fixed salt followed by a suffix (mostly a decimal nonce).
+// Nothing is allocated per hash, nonces and hex digits are written into
+// stack buffers, and searches run on all cores in chunks of nonces.
+
+pub type Digest = [u8; 16];
+
+// Hasher with the salt already absorbed.
+#[derive(Clone)]
+pub struct Salted {
+    hasher: Md5,
+}
+
+impl Salted {
+    pub fn new(salt: &str) -> Salted {
+        Salted {
+            hasher: Md5::new_with_prefix(salt.as_bytes()),
+        }
+    }
+
+    pub fn hash(&self, suffix: &[u8]) -> Digest {
+        let mut hasher = self.hasher.clone();
+        hasher.update(suffix);
+        hasher.finalize().into()
+    }
+
+    pub fn hash_nonce(&self, nonce: u64) -> Digest {
+        let mut buf = [0; 20];
+        self.hash(decimal(nonce, &mut buf))
+    }
+}
+
+pub fn md5(data: &[u8]) -> Digest {
+    Md5::digest(data).into()
+}
+
+// The i-th hex digit.
+pub fn nibble(digest: &Digest, i: usize) -> u8 {
+    let b = digest[i / 2];
+    if i.is_multiple_of(2) {
+        b >> 4
+    } else {
+        b & 0xf
+    }
+}
+
+pub fn leading_zeros(digest: &Digest) -> usize {
+    (0..32).take_while(|i| nibble(digest, *i) == 0).count()
+}
+
+// Checks the hex representation for a prefix, e.g. "00000".
+pub fn has_prefix(digest: &Digest, prefix: &str) -> bool {
+    let mut buf = [0; 32];
+    hex(digest, &mut buf).starts_with(prefix)
+}
+
+// Lowercase hex representation.
+pub fn hex<'a>(digest: &Digest, buf: &'a mut [u8; 32]) -> &'a str {
+    const DIGITS: &[u8; 16] = b"0123456789abcdef";
+    for (i, b) in digest.iter().enumerate() {
+        buf[2 * i] = DIGITS[(b >> 4) as usize];
+        buf[2 * i + 1] = DIGITS[(b & 0xf) as usize];
+    }
+    std::str::from_utf8(buf).unwrap()
+}
+
+fn decimal(mut n: u64, buf: &mut [u8; 20]) -> &[u8] {
+    let mut i = buf.len();
+    loop {
+        i -= 1;
+        buf[i] = b'0' + (n % 10) as u8;
+        n /= 10;
+        if n == 0 {
+            break;
+        }
+    }
+    &buf[i..]
+}
+
+pub struct Search {
+    salted: Salted,
+    start: u64,
+    chunk: u64,
+    threads: usize,
+}
+
+impl Search {
+    pub fn new(salt: &str) -> Search {
+        Search {
+            salted: Salted::new(salt),
+            start: 0,
+            chunk: 4096,
+            threads: thread::available_parallelism().map_or(1, |n| n.get()),
+        }
+    }
+
+    pub fn start(mut self, start: u64) -> Search {
+        self.start = start;
+        self
+    }
+
+    pub fn threads(mut self, threads: usize) -> Search {
+        self.threads = threads.max(1);
+        self
+    }
+
+    // All matching nonces with their hashes, in increasing order. Runs
+    // forever if nothing matches.
+    pub fn matches<F>(self, pred: F) -> Matches<F>
+    where
+        F: Fn(&Digest) -> bool + Sync,
+    {
+        Matches {
+            next: self.start,
+            search: self,
+            pred,
+            found: VecDeque::new(),
+        }
+    }
+
+    pub fn first<F>(self, pred: F) -> (u64, Digest)
+    where
+        F: Fn(&Digest) -> bool + Sync,
+    {
+        self.matches(pred).next().unwrap()
+    }
+}
+
+pub struct Matches<F> {
+    search: Search,
+    pred: F,
+    next: u64,
+    found: VecDeque<(u64, Digest)>,
+}
+
+impl<F: Fn(&Digest) -> bool + Sync> Matches<F> {
+    // searches the next chunk on every thread
+    fn search_chunks(&mut self) {
+        let Search {
+            salted,
+            chunk,
+            threads,
+            ..
+        } = &self.search;
+        let pred = &self.pred;
+        let first = self.next;
+        let results: Vec<Vec<(u64, Digest)>> = thread::scope(|s| {
+            let handles: Vec<_> = (0..*threads as u64)
+                .map(|t| {
+                    let from = first + t * chunk;
+                    s.spawn(move || {
+                        (from..from + chunk)
+                            .map(|n| (n, salted.hash_nonce(n)))
+                            .filter(|(_, d)| pred(d))
+                            .collect()
+                    })
+                })
+                .collect();
+            handles.into_iter().map(|h| h.join().unwrap()).collect()
+        });
+        self.found.extend(results.into_iter().flatten());
+        self.next += *threads as u64 * chunk;
+    }
+}
+
+impl<F: Fn(&Digest) -> bool + Sync> Iterator for Matches<F> {
+    type Item = (u64, Digest);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while self.found.is_empty() {
+            cancel::check_now();
+            self.search_chunks();
+        }
+        self.found.pop_front()
+    }
+}
+
+// Key stretching (2016 day 14): the hash of the nonce is hashed again
+// `rounds` times as hex string. Hashes are computed in parallel chunks
+// and memoized, as the puzzle looks ahead a thousand indices.
+pub struct Stretched {
+    salted: Salted,
+    rounds: usize,
+    threads: usize,
+    cache: Vec<Digest>,
+}
+
+impl Stretched {
+    pub fn new(salt: &str, rounds: usize) -> Stretched {
+        Stretched {
+            salted: Salted::new(salt),
+            rounds,
+            threads: thread::available_parallelism().map_or(1, |n| n.get()),
+            cache: vec![],
+        }
+    }
+
+    pub fn get(&mut self, idx: usize) -> Digest {
+        while self.cache.len() <= idx {
+            cancel::check_now();
+            self.extend();
+        }
+        self.cache[idx]
+    }
+
+    fn extend(&mut self) {
+        const CHUNK: usize = 256;
+        let (salted, rounds) = (&self.salted, self.rounds);
+        let first = self.cache.len();
+        let results: Vec<Vec<Digest>> = thread::scope(|s| {
+            let handles: Vec<_> = (0..self.threads)
+                .map(|t| {
+                    let from = first + t * CHUNK;
+                    s.spawn(move || {
+                        (from..from + CHUNK)
+                            .map(|n| stretch(salted.hash_nonce(n as u64), rounds))
+                            .collect()
+                    })
+                })
+                .collect();
+            handles.into_iter().map(|h| h.join().unwrap()).collect()
+        });
+        self.cache.extend(results.into_iter().flatten());
+    }
+}
+
+pub fn stretch(mut digest: Digest, rounds: usize) -> Digest {
+    let mut buf = [0; 32];
+    for _ in 0..rounds {
+        digest = md5(hex(&digest, &mut buf).as_bytes());
+    }
+    digest
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_hex() {
+        let digest = md5(b"abc");
+        let mut buf = [0; 32];
+        assert_eq!(hex(&digest, &mut buf), "900150983cd24fb0d6963f7d28e17f72");
+        assert_eq!(nibble(&digest, 0), 9);
+        assert_eq!(nibble(&digest, 3), 1);
+        assert!(has_prefix(&digest, "9001"));
+        assert_eq!(leading_zeros(&Salted::new("abc").hash_nonce(3231929)), 5);
+
+        let mut buf = [0; 20];
+        assert_eq!(decimal(0, &mut buf), b"0");
+        assert_eq!(decimal(u64::MAX, &mut buf), b"18446744073709551615");
+    }
+
+    #[test]
+    fn test_search() {
+        // the first door password character of 2016 day 5
+        let (n, digest) = Search::new("abc")
+            .start(3_220_000)
+            .threads(2)
+            .first(|d| leading_zeros(d) >= 5);
+        assert_eq!(n, 3231929);
+        assert_eq!(nibble(&digest, 5), 1);
+    }
+
+    #[test]
+    fn test_stretch() {
+        let mut stretched = Stretched::new("abc", 2);
+        let mut buf = [0; 32];
+        let once = hex(&md5(b"abc5"), &mut buf).to_string();
+        let mut buf = [0; 32];
+        let twice = hex(&md5(once.as_bytes()), &mut buf).to_string();
+        assert_eq!(stretched.get(5), md5(twice.as_bytes()));
+        assert_eq!(stretched.get(300), stretch(md5(b"abc300"), 2));
+    }
+}
@@ -0,0 +1,223 @@
+use std::collections::BTreeMap;
+use std::fmt::Debug;
+
+// Inference of an opcode number -> operation mapping from observed
+// samples, for puzzles like 2018 day 16. Works with any instruction set:
+// the caller provides the operations and a function executing them.
+
+pub struct Sample<I, R> {
+    pub opcode: usize,
+    // operands, without the opcode
+    pub instr: I,
+    pub before: R,
+    pub after: R,
+}
+
+pub struct Inference<O> {
+    // operations consistent with each sample
+    pub matching: Vec<Vec<O>>,
+    // operations consistent with all samples of an opcode
+    pub candidates: BTreeMap<usize, Vec<O>>,
+    // per opcode: each excluded operation with the first sample ruling it out
+    pub ruled_out: BTreeMap<usize, Vec<(O, usize)>>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum SolveError<O> {
+    NoSolution,
+    // two different assignments, both consistent with the samples
+    Ambiguous(BTreeMap<usize, O>, BTreeMap<usize, O>),
+}
+
+// exec returns None if the operation can't be executed at all (e.g. on
+// invalid registers).
+pub fn infer<O, I, R, F>(ops: &[O], samples: &[Sample<I, R>], exec: F) -> Inference<O>
+where
+    O: Copy + PartialEq,
+    R: PartialEq,
+    F: Fn(&O, &I, &R) -> Option<R>,
+{
+    let matching: Vec<Vec<O>> = samples
+        .iter()
+        .map(|s| {
+            ops.iter()
+                .filter(|op| exec(op, &s.instr, &s.before).is_some_and(|r| r == s.after))
+                .copied()
+                .collect()
+        })
+        .collect();
+
+    let mut candidates = BTreeMap::new();
+    let mut ruled_out = BTreeMap::new();
+    for (idx, sample) in samples.iter().enumerate() {
+        let cands: &mut Vec<O> = candidates
+            .entry(sample.opcode)
+            .or_insert_with(|| ops.to_vec());
+        let out: &mut Vec<(O, usize)> = ruled_out.entry(sample.opcode).or_default();
+        for op in cands.iter().filter(|op| !matching[idx].contains(op)) {
+            out.push((*op, idx));
+        }
+        cands.retain(|op| matching[idx].contains(op));
+    }
+
+    Inference {
+        matching,
+        candidates,
+        ruled_out,
+    }
+}
+
+impl<O: Copy + PartialEq + Debug> Inference<O> {
+    // Finds the assignment of distinct operations to the opcodes, by
+    // constraint propagation and backtracking. Fails unless it is unique.
+    pub fn solve(&self) -> Result<BTreeMap<usize, O>, SolveError<O>> {
+        let mut solutions = vec![];
+        search(self.candidates.clone(), &mut solutions);
+        match solutions.len() {
+            0 => Err(SolveError::NoSolution),
+            1 => Ok(solutions.pop().unwrap()),
+            _ => {
+                let second = solutions.pop().unwrap();
+                Err(SolveError::Ambiguous(solutions.pop().unwrap(), second))
+            }
+        }
+    }
+
+    // Which samples ruled out which operations for an opcode.
+    pub fn explain(&self, opcode: usize) -> String {
+        let mut lines = vec![format!(
+            "opcode {}: candidates {:?}",
+            opcode,
+            self.candidates.get(&opcode).cloned().unwrap_or_default()
+        )];
+        for (op, idx) in self.ruled_out.get(&opcode).into_iter().flatten() {
+            lines.push(format!("  {:?} ruled out by sample {}", op, idx));
+        }
+        lines.join("\n")
+    }
+}
+
+// Removes operations which are known to belong to another opcode, until
+// nothing changes. Returns false on a contradiction.
+fn propagate<O: Copy + PartialEq>(cands: &mut BTreeMap<usize, Vec<O>>) -> bool {
+    loop {
+        let mut changed = false;
+        let fixed: Vec<(usize, O)> = cands
+            .iter()
+            .filter(|(_, c)| c.len() == 1)
+            .map(|(k, c)| (*k, c[0]))
+            .collect();
+        for (opcode, op) in fixed {
+            for (k, c) in cands.iter_mut() {
+                if *k != opcode && c.contains(&op) {
+                    c.retain(|o| *o != op);
+                    changed = true;
+                }
+            }
+        }
+        if cands.values().any(|c| c.is_empty()) {
+            return false;
+        }
+        if !changed {
+            return true;
+        }
+    }
+}
+
+// Collects up to two solutions.
+fn search<O: Copy + PartialEq>(
+    mut cands: BTreeMap<usize, Vec<O>>,
+    solutions: &mut Vec<BTreeMap<usize, O>>,
+) {
+    if solutions.len() >= 2 || !propagate(&mut cands) {
+        return;
+    }
+    let open = cands
+        .iter()
+        .filter(|(_, c)| c.len() > 1)
+        .min_by_key(|(_, c)| c.len());
+    match open {
+        None => {
+            solutions.push(cands.into_iter().map(|(k, c)| (k, c[0])).collect());
+        }
+        Some((&opcode, c)) => {
+            for op in c.clone() {
+                let mut next = cands.clone();
+                next.insert(opcode, vec![op]);
+                search(next, solutions);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Copy, Clone, Debug, PartialEq, Eq)]
+    enum Op {
+        Add,
+        Mul,
+        Sub,
+    }
+
+    fn exec(op: &Op, x: &i64, acc: &i64) -> Option<i64> {
+        match op {
+            Op::Add => Some(acc + x),
+            Op::Mul => Some(acc * x),
+            Op::Sub => Some(acc - x),
+        }
+    }
+
+    fn sample(opcode: usize, x: i64, before: i64, after: i64) -> Sample<i64, i64> {
+        Sample {
+            opcode,
+            instr: x,
+            before,
+            after,
+        }
+    }
+
+    const OPS: [Op; 3] = [Op::Add, Op::Mul, Op::Sub];
+
+    #[test]
+    fn test_infer() {
+        let samples = [
+            sample(0, 2, 2, 4),
+            sample(1, 2, 2, 4),
+            sample(1, 3, 1, 3),
+            sample(2, 1, 1, 0),
+        ];
+        let inference = infer(&OPS, &samples, exec);
+        assert_eq!(inference.matching[0], vec![Op::Add, Op::Mul]);
+        assert_eq!(inference.candidates[&0], vec![Op::Add, Op::Mul]);
+        assert_eq!(inference.candidates[&1], vec![Op::Mul]);
+        assert_eq!(inference.ruled_out[&1], vec![(Op::Sub, 1), (Op::Add, 2)]);
+
+        let solution = inference.solve().unwrap();
+        assert_eq!(
+            solution,
+            BTreeMap::from([(0, Op::Add), (1, Op::Mul), (2, Op::Sub)])
+        );
+        assert_eq!(
+            inference.explain(1),
+            "opcode 1: candidates [Mul]\n  Sub ruled out by sample 1\n  Add ruled out by sample 2"
+        );
+    }
+
+    #[test]
+    fn test_backtracking() {
+        // nothing to propagate: 0 and 1 are {Add, Mul}, 2 can be anything
+        // but has to be Sub, which leaves two ways for 0 and 1
+        let samples = [sample(0, 2, 2, 4), sample(1, 2, 2, 4), sample(2, 0, 0, 0)];
+        let inference = infer(&OPS, &samples, exec);
+        assert_eq!(inference.candidates[&2], OPS.to_vec());
+        assert!(matches!(inference.solve(), Err(SolveError::Ambiguous(..))));
+
+        let samples = [sample(0, 2, 2, 4), sample(1, 2, 2, 4), sample(2, 1, 0, 0)];
+        let inference = infer(&OPS, &samples, exec);
+        // 2 is Mul, so 0 and 1 both would have to be Add
+        assert_eq!(inference.candidates[&2], vec![Op::Mul]);
+        assert_eq!(inference.solve(), Err(SolveError::NoSolution));
+    }
+}
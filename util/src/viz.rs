@@ -0,0 +1,279 @@
+use crate::grid2d::Grid2D;
+use crate::image::{ImageFormat, Rgb};
+use std::collections::HashMap;
+use std::io::Write;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+use std::time::Duration;
+
+// Frame recording for visual simulations. Solutions call frame() with the
+// current state of their map; this is a no-op unless the runner enabled
+// a recorder (--visualize).
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum FrameFormat {
+    Text,
+    Ppm,
+    Gif,
+}
+
+#[derive(Clone, Debug)]
+pub enum Output {
+    Terminal,
+    Dir(PathBuf, FrameFormat),
+}
+
+#[derive(Clone, Debug)]
+pub struct Config {
+    pub output: Output,
+    // only every n-th frame is recorded
+    pub stride: usize,
+    pub delay: Duration,
+    // pixels per tile in image formats
+    pub scale: usize,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Config {
+            output: Output::Terminal,
+            stride: 1,
+            delay: Duration::from_millis(50),
+            scale: 4,
+        }
+    }
+}
+
+struct Recorder {
+    config: Config,
+    label: String,
+    count: usize,
+    gif_frames: Vec<Grid2D<char>>,
+}
+
+static ENABLED: AtomicBool = AtomicBool::new(false);
+static RECORDER: Mutex<Option<Recorder>> = Mutex::new(None);
+
+pub fn enable(config: Config) {
+    *RECORDER.lock().unwrap() = Some(Recorder {
+        config,
+        label: String::from("frames"),
+        count: 0,
+        gif_frames: vec![],
+    });
+    ENABLED.store(true, Ordering::Relaxed);
+}
+
+pub fn is_enabled() -> bool {
+    ENABLED.load(Ordering::Relaxed)
+}
+
+// Start a new recording, e.g. for the next star. The label names the
+// output sub-directory.
+pub fn begin(label: &str) {
+    if !is_enabled() {
+        return;
+    }
+    let mut guard = RECORDER.lock().unwrap();
+    if let Some(rec) = guard.as_mut() {
+        rec.label = label.to_string();
+        rec.count = 0;
+        rec.gif_frames.clear();
+        match &rec.config.output {
+            Output::Terminal => {
+                print!("\x1b[2J");
+            }
+            Output::Dir(path, _) => {
+                std::fs::create_dir_all(path.join(label)).unwrap();
+            }
+        }
+    }
+}
+
+pub fn frame(grid: &Grid2D<char>) {
+    if !is_enabled() {
+        return;
+    }
+    let mut guard = RECORDER.lock().unwrap();
+    if let Some(rec) = guard.as_mut() {
+        rec.count += 1;
+        if (rec.count - 1) % rec.config.stride.max(1) != 0 {
+            return;
+        }
+        let frame_no = (rec.count - 1) / rec.config.stride.max(1);
+        match &rec.config.output {
+            Output::Terminal => {
+                let mut out = std::io::stdout().lock();
+                writeln!(out, "\x1b[H{}", grid).unwrap();
+                out.flush().unwrap();
+                std::thread::sleep(rec.config.delay);
+            }
+            Output::Dir(path, FrameFormat::Text) => {
+                let filename = path.join(&rec.label).join(format!("{:06}.txt", frame_no));
+                std::fs::write(filename, format!("{}\n", grid)).unwrap();
+            }
+            Output::Dir(path, FrameFormat::Ppm) => {
+                let filename = path.join(&rec.label).join(format!("{:06}.ppm", frame_no));
+                let data = grid.to_image(ImageFormat::Ppm, rec.config.scale, |c| char_color(*c));
+                std::fs::write(filename, data).unwrap();
+            }
+            Output::Dir(_, FrameFormat::Gif) => {
+                rec.gif_frames.push(grid.clone());
+            }
+        }
+    }
+}
+
+// Finish the current recording; animated formats are written here.
+pub fn finish() {
+    if !is_enabled() {
+        return;
+    }
+    let mut guard = RECORDER.lock().unwrap();
+    if let Some(rec) = guard.as_mut() {
+        if let Output::Dir(path, FrameFormat::Gif) = &rec.config.output {
+            if !rec.gif_frames.is_empty() {
+                let filename = path.join(&rec.label).join("anim.gif");
+                let delay_cs = (rec.config.delay.as_millis() / 10) as u16;
+                match gif(&rec.gif_frames, rec.config.scale, delay_cs) {
+                    Ok(data) => std::fs::write(filename, data).unwrap(),
+                    Err(e) => eprintln!("Can't write {}: {}", filename.display(), e),
+                }
+            }
+        }
+        rec.gif_frames.clear();
+    }
+}
+
+pub fn char_color(c: char) -> Rgb {
+    match c {
+        ' ' | '.' => [0x10, 0x10, 0x18],
+        '#' => [0xd0, 0xd0, 0xd0],
+        '@' => [0xff, 0xd7, 0x00],
+        'O' | 'o' | '[' | ']' => [0xc8, 0x8a, 0x3c],
+        '~' | '|' => [0x30, 0x70, 0xe0],
+        _ => {
+            // some stable, reasonably bright color for everything else
+            let h = (c as u32).wrapping_mul(2654435761);
+            [
+                0x40 + (h >> 24) as u8 % 0xc0,
+                0x40 + (h >> 16) as u8 % 0xc0,
+                0x40 + (h >> 8) as u8 % 0xc0,
+            ]
+        }
+    }
+}
+
+// Animated GIF with a shared palette of all characters in the frames.
+// The image data uses "uncompressed" LZW: only literal codes, with a clear
+// code before the code table would grow beyond 9 bits.
+fn gif(frames: &[Grid2D<char>], scale: usize, delay_cs: u16) -> Result<Vec<u8>, String> {
+    let mut palette: HashMap<char, u8> = HashMap::new();
+    let mut colors = vec![];
+    for grid in frames {
+        for c in grid.iter() {
+            if !palette.contains_key(c) && palette.len() < 256 {
+                palette.insert(*c, palette.len() as u8);
+                colors.push(char_color(*c));
+            }
+        }
+    }
+
+    let width = frames[0].width() as usize * scale;
+    let height = frames[0].height() as usize * scale;
+    let (Ok(width16), Ok(height16)) = (u16::try_from(width), u16::try_from(height)) else {
+        return Err(format!("{}x{} exceeds the GIF size limit", width, height));
+    };
+
+    let mut data = b"GIF89a".to_vec();
+    data.extend(width16.to_le_bytes());
+    data.extend(height16.to_le_bytes());
+    data.extend([0xf7, 0, 0]);
+    for i in 0..256 {
+        data.extend(colors.get(i).unwrap_or(&[0, 0, 0]));
+    }
+    // loop forever
+    data.extend([0x21, 0xff, 0x0b]);
+    data.extend(b"NETSCAPE2.0");
+    data.extend([0x03, 0x01, 0x00, 0x00, 0x00]);
+
+    for grid in frames {
+        data.extend([0x21, 0xf9, 0x04, 0x00]);
+        data.extend(delay_cs.to_le_bytes());
+        data.extend([0x00, 0x00]);
+
+        data.push(0x2c);
+        data.extend([0, 0, 0, 0]);
+        data.extend(width16.to_le_bytes());
+        data.extend(height16.to_le_bytes());
+        data.push(0);
+
+        let mut indices = Vec::with_capacity(width * height);
+        for y in 0..height {
+            for c in grid.row((y / scale) as i64).unwrap() {
+                let idx = *palette.get(c).unwrap_or(&0);
+                indices.extend(std::iter::repeat_n(idx, scale));
+            }
+        }
+
+        data.push(8);
+        let lzw = lzw_literal(&indices);
+        for block in lzw.chunks(255) {
+            data.push(block.len() as u8);
+            data.extend(block);
+        }
+        data.push(0);
+    }
+    data.push(0x3b);
+    Ok(data)
+}
+
+fn lzw_literal(indices: &[u8]) -> Vec<u8> {
+    const CLEAR: u32 = 256;
+    const END: u32 = 257;
+    let mut out = vec![];
+    let mut acc: u32 = 0;
+    let mut bits = 0;
+    let mut emit = |code: u32, out: &mut Vec<u8>| {
+        acc |= code << bits;
+        bits += 9;
+        while bits >= 8 {
+            out.push((acc & 0xff) as u8);
+            acc >>= 8;
+            bits -= 8;
+        }
+    };
+    for chunk in indices.chunks(250) {
+        emit(CLEAR, &mut out);
+        for idx in chunk {
+            emit(*idx as u32, &mut out);
+        }
+    }
+    emit(END, &mut out);
+    if bits > 0 {
+        out.push((acc & 0xff) as u8);
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_gif() {
+        let frames = vec![
+            Grid2D::new("#.\n.#").unwrap(),
+            Grid2D::new(".#\n#.").unwrap(),
+        ];
+        let data = gif(&frames, 3, 5).unwrap();
+        assert_eq!(&data[..6], b"GIF89a");
+        assert_eq!(&data[6..10], &[6, 0, 6, 0]);
+        assert_eq!(data.last(), Some(&0x3b));
+        assert!(data.iter().filter(|b| **b == 0x2c).count() >= 2);
+
+        let wide = vec![Grid2D::new(&".".repeat(20000)).unwrap()];
+        assert!(gif(&wide, 4, 5).is_err());
+    }
+}
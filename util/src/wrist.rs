@@ -1,10 +1,14 @@
-use std::collections::HashSet;
+use crate::asmparse::{expect_operands, ParseError, Source, Token};
+use crate::cancel;
+use crate::opinfer::{self, Inference, Sample};
+use std::fmt;
 
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
 pub struct Instruction {
-    op: Op,
-    a: usize,
-    b: usize,
-    c: usize,
+    pub op: Op,
+    pub a: usize,
+    pub b: usize,
+    pub c: usize,
 }
 
 pub type Registers = [usize; 6];
@@ -36,259 +40,364 @@ pub struct Wrist {
     pub ip_reg: usize,
 }
 
-impl Wrist {
-    pub fn run(&mut self) {
-        while self.ip < self.program.len() {
-            self.reg[self.ip_reg] = self.ip;
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Hook {
+    Continue,
+    // do not execute the instruction, go on with the next one
+    Skip,
+    Stop,
+}
 
-            let instr = &self.program[self.ip];
-            self.reg = exec_op(instr, &self.reg).unwrap();
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Exit {
+    Halted,
+    Stopped,
+}
 
-            self.ip = self.reg[self.ip_reg];
-            self.ip += 1;
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum WristError {
+    InvalidRegister { ip: usize, reg: usize },
+}
+
+impl fmt::Display for WristError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            WristError::InvalidRegister { ip, reg } => {
+                write!(f, "invalid register {} at ip {}", reg, ip)
+            }
         }
     }
+}
+
+impl Wrist {
+    pub fn run(&mut self) -> Result<(), WristError> {
+        self.run_with(|_, _, _| Hook::Continue).map(|_| ())
+    }
 
-    pub fn run_d21_s1(&mut self) -> Option<usize> {
+    // The hook is called before each instruction with the ip, the
+    // instruction and the register file (which it may modify).
+    pub fn run_with<F>(&mut self, mut hook: F) -> Result<Exit, WristError>
+    where
+        F: FnMut(usize, &Instruction, &mut Registers) -> Hook,
+    {
         while self.ip < self.program.len() {
+            cancel::check();
             self.reg[self.ip_reg] = self.ip;
 
             let instr = &self.program[self.ip];
-            if instr.op == Op::Eqrr {
-                return Some(self.reg[1]);
+            match hook(self.ip, instr, &mut self.reg) {
+                Hook::Continue => {
+                    self.reg = exec_op(instr, &self.reg)
+                        .map_err(|reg| WristError::InvalidRegister { ip: self.ip, reg })?;
+                }
+                Hook::Skip => {}
+                Hook::Stop => {
+                    return Ok(Exit::Stopped);
+                }
             }
-            self.reg = exec_op(instr, &self.reg).unwrap();
 
             self.ip = self.reg[self.ip_reg];
             self.ip += 1;
         }
-        None
+        Ok(Exit::Halted)
     }
 
-    pub fn run_d21_s2(&mut self) -> Option<usize> {
-        let mut values = HashSet::new();
-        let mut last_inserted = 0;
-        while self.ip < self.program.len() {
-            self.reg[self.ip_reg] = self.ip;
+    pub fn new_from_str(input: &str) -> Wrist {
+        Wrist::parse(input).unwrap_or_else(|e| panic!("{}", e))
+    }
 
-            let instr = &self.program[self.ip];
-            if instr.op == Op::Eqrr {
-                if !values.insert(self.reg[1]) {
-                    return Some(last_inserted);
-                } else {
-                    last_inserted = self.reg[1];
-                }
+    // Besides the "#ip" directive, programs may contain comments and labels
+    // (see asmparse). A label used as an immediate operand evaluates to the
+    // address before it, so that writing it to the ip register continues
+    // at the label.
+    pub fn parse(input: &str) -> Result<Wrist, ParseError> {
+        let source = Source::new(input)?;
+        let mut ip_reg = None;
+        for tokens in &source.directives {
+            if tokens[0].text != "#ip" {
+                return Err(tokens[0].error(format!("unknown directive '{}'", tokens[0].text)));
             }
-            self.reg = exec_op(instr, &self.reg).unwrap();
-
-            self.ip = self.reg[self.ip_reg];
-            self.ip += 1;
+            if ip_reg.is_some() {
+                return Err(tokens[0].error(String::from("duplicate #ip directive")));
+            }
+            expect_operands(tokens, 1)?;
+            ip_reg = Some(parse_reg(&tokens[1])?);
         }
-        None
+        let ip_reg = ip_reg.ok_or(ParseError {
+            line: 1,
+            col: 1,
+            msg: String::from("missing #ip directive"),
+        })?;
+
+        let program = source
+            .instructions
+            .iter()
+            .map(|tokens| parse_instruction(&source, tokens))
+            .collect::<Result<_, _>>()?;
+        Ok(Wrist {
+            program,
+            reg: [0, 0, 0, 0, 0, 0],
+            ip: 0,
+            ip_reg,
+        })
     }
+}
 
-    pub fn run_y2018_s2(&mut self, max_cycles: usize) -> usize {
-        let mut cycles = 0;
-        let mut max_reg = 0;
+#[derive(Copy, Clone, PartialEq, Eq)]
+enum Operand {
+    Reg,
+    Imm,
+    Ignored,
+}
 
-        while self.ip < self.program.len() && cycles < max_cycles {
-            self.reg[self.ip_reg] = self.ip;
+impl Op {
+    pub const ALL: [Op; 16] = [
+        Op::Addr,
+        Op::Addi,
+        Op::Mulr,
+        Op::Muli,
+        Op::Banr,
+        Op::Bani,
+        Op::Borr,
+        Op::Bori,
+        Op::Setr,
+        Op::Seti,
+        Op::Gtir,
+        Op::Gtri,
+        Op::Gtrr,
+        Op::Eqir,
+        Op::Eqri,
+        Op::Eqrr,
+    ];
 
-            let instr = &self.program[self.ip];
-            self.reg = exec_op(instr, &self.reg).unwrap();
+    pub fn mnemonic(&self) -> &'static str {
+        match self {
+            Op::Addr => "addr",
+            Op::Addi => "addi",
+            Op::Mulr => "mulr",
+            Op::Muli => "muli",
+            Op::Banr => "banr",
+            Op::Bani => "bani",
+            Op::Borr => "borr",
+            Op::Bori => "bori",
+            Op::Setr => "setr",
+            Op::Seti => "seti",
+            Op::Gtir => "gtir",
+            Op::Gtri => "gtri",
+            Op::Gtrr => "gtrr",
+            Op::Eqir => "eqir",
+            Op::Eqri => "eqri",
+            Op::Eqrr => "eqrr",
+        }
+    }
 
-            self.ip = self.reg[self.ip_reg];
-            self.ip += 1;
+    pub fn from_mnemonic(s: &str) -> Option<Op> {
+        Op::ALL.into_iter().find(|op| op.mnemonic() == s)
+    }
 
-            max_reg = max_reg.max((0..5).map(|i| self.reg[i]).max().unwrap());
-            cycles += 1;
+    // kinds of the operands a and b
+    fn operands(&self) -> (Operand, Operand) {
+        use Operand::*;
+        match self {
+            Op::Addr | Op::Mulr | Op::Banr | Op::Borr | Op::Gtrr | Op::Eqrr => (Reg, Reg),
+            Op::Addi | Op::Muli | Op::Bani | Op::Bori | Op::Gtri | Op::Eqri => (Reg, Imm),
+            Op::Gtir | Op::Eqir => (Imm, Reg),
+            Op::Setr => (Reg, Ignored),
+            Op::Seti => (Imm, Ignored),
         }
+    }
+}
 
-        max_reg
+fn parse_reg(token: &Token) -> Result<usize, ParseError> {
+    let reg: usize = token.parse("register")?;
+    if reg >= 6 {
+        return Err(token.error(format!("invalid register {}", reg)));
     }
+    Ok(reg)
+}
 
-    pub fn new_from_str(input: &str) -> Wrist {
-        let ip_reg = input
-            .lines()
-            .next()
-            .unwrap()
-            .split_whitespace()
-            .nth(1)
-            .unwrap()
-            .parse::<usize>()
-            .unwrap();
-        let program: Vec<_> = input
-            .lines()
-            .skip(1)
-            .map(|line| {
-                let mut it = line.split_whitespace();
-                let op_str = it.next().unwrap();
-                let op = match op_str {
-                    "addr" => Op::Addr,
-                    "addi" => Op::Addi,
-                    "mulr" => Op::Mulr,
-                    "muli" => Op::Muli,
-                    "banr" => Op::Banr,
-                    "bani" => Op::Bani,
-                    "borr" => Op::Borr,
-                    "bori" => Op::Bori,
-                    "setr" => Op::Setr,
-                    "seti" => Op::Seti,
-                    "gtir" => Op::Gtir,
-                    "gtri" => Op::Gtri,
-                    "gtrr" => Op::Gtrr,
-                    "eqir" => Op::Eqir,
-                    "eqri" => Op::Eqri,
-                    "eqrr" => Op::Eqrr,
-                    _ => {
-                        panic!("Unknown op: {}", op_str);
-                    }
-                };
-                let a = it.next().unwrap().parse::<usize>().unwrap();
-                let b = it.next().unwrap().parse::<usize>().unwrap();
-                let c = it.next().unwrap().parse::<usize>().unwrap();
-                Instruction { op, a, b, c }
-            })
-            .collect();
-        Wrist {
-            program,
-            reg: [0, 0, 0, 0, 0, 0],
-            ip: 0,
-            ip_reg,
+fn parse_instruction(source: &Source, tokens: &[Token]) -> Result<Instruction, ParseError> {
+    let op = Op::from_mnemonic(tokens[0].text)
+        .ok_or_else(|| tokens[0].error(format!("unknown instruction '{}'", tokens[0].text)))?;
+    expect_operands(tokens, 3)?;
+
+    let operand = |kind: Operand, token: &Token| match kind {
+        Operand::Reg => parse_reg(token),
+        Operand::Imm if source.is_label(token) => source
+            .label(token)?
+            .checked_sub(1)
+            .ok_or_else(|| token.error(format!("label '{}' has no address before it", token.text))),
+        Operand::Imm => token.parse("number or label"),
+        Operand::Ignored => token.parse("number"),
+    };
+    let (kind_a, kind_b) = op.operands();
+    Ok(Instruction {
+        op,
+        a: operand(kind_a, &tokens[1])?,
+        b: operand(kind_b, &tokens[2])?,
+        c: operand(Operand::Reg, &tokens[3])?,
+    })
+}
+
+impl fmt::Display for Op {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.mnemonic())
+    }
+}
+
+impl fmt::Display for Instruction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} {} {} {}", self.op, self.a, self.b, self.c)
+    }
+}
+
+impl fmt::Display for Wrist {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "#ip {}", self.ip_reg)?;
+        for instr in &self.program {
+            write!(f, "\n{}", instr)?;
         }
+        Ok(())
     }
 }
 
-fn exec_op(instr: &Instruction, regs_in: &Registers) -> Option<Registers> {
+// Works on any number of registers (the device in 2018 day 16 has 4).
+// Returns the first invalid register index as error.
+pub fn exec_op<const N: usize>(
+    instr: &Instruction,
+    regs_in: &[usize; N],
+) -> Result<[usize; N], usize> {
+    let r = |i: usize| if i < N { Ok(regs_in[i]) } else { Err(i) };
+    let (a, b) = (instr.a, instr.b);
+    let val = match instr.op {
+        Op::Addr => r(a)? + r(b)?,
+        Op::Addi => r(a)? + b,
+        Op::Mulr => r(a)? * r(b)?,
+        Op::Muli => r(a)? * b,
+        Op::Banr => r(a)? & r(b)?,
+        Op::Bani => r(a)? & b,
+        Op::Borr => r(a)? | r(b)?,
+        Op::Bori => r(a)? | b,
+        Op::Setr => r(a)?,
+        Op::Seti => a,
+        Op::Gtir => usize::from(a > r(b)?),
+        Op::Gtri => usize::from(r(a)? > b),
+        Op::Gtrr => usize::from(r(a)? > r(b)?),
+        Op::Eqir => usize::from(a == r(b)?),
+        Op::Eqri => usize::from(r(a)? == b),
+        Op::Eqrr => usize::from(r(a)? == r(b)?),
+    };
+    if instr.c >= N {
+        return Err(instr.c);
+    }
     let mut regs_out = *regs_in;
-    match instr.op {
-        Op::Addr => {
-            if instr.a < 6 && instr.b < 6 && instr.c < 6 {
-                regs_out[instr.c] = regs_in[instr.a] + regs_in[instr.b];
-                Some(regs_out)
-            } else {
-                None
-            }
-        }
-        Op::Addi => {
-            if instr.a < 6 && instr.c < 6 {
-                regs_out[instr.c] = regs_in[instr.a] + instr.b;
-                Some(regs_out)
-            } else {
-                None
-            }
-        }
-        Op::Mulr => {
-            if instr.a < 6 && instr.b < 6 && instr.c < 6 {
-                regs_out[instr.c] = regs_in[instr.a] * regs_in[instr.b];
-                Some(regs_out)
-            } else {
-                None
-            }
-        }
-        Op::Muli => {
-            if instr.a < 6 && instr.c < 6 {
-                regs_out[instr.c] = regs_in[instr.a] * instr.b;
-                Some(regs_out)
-            } else {
-                None
-            }
-        }
-        Op::Banr => {
-            if instr.a < 6 && instr.b < 6 && instr.c < 6 {
-                regs_out[instr.c] = regs_in[instr.a] & regs_in[instr.b];
-                Some(regs_out)
-            } else {
-                None
-            }
-        }
-        Op::Bani => {
-            if instr.a < 6 && instr.c < 6 {
-                regs_out[instr.c] = regs_in[instr.a] & instr.b;
-                Some(regs_out)
-            } else {
-                None
-            }
-        }
-        Op::Borr => {
-            if instr.a < 6 && instr.b < 6 && instr.c < 6 {
-                regs_out[instr.c] = regs_in[instr.a] | regs_in[instr.b];
-                Some(regs_out)
-            } else {
-                None
-            }
-        }
-        Op::Bori => {
-            if instr.a < 6 && instr.c < 6 {
-                regs_out[instr.c] = regs_in[instr.a] | instr.b;
-                Some(regs_out)
-            } else {
-                None
-            }
-        }
-        Op::Setr => {
-            if instr.a < 6 && instr.c < 6 {
-                regs_out[instr.c] = regs_in[instr.a];
-                Some(regs_out)
-            } else {
-                None
-            }
-        }
-        Op::Seti => {
-            if instr.c < 6 {
-                regs_out[instr.c] = instr.a;
-                Some(regs_out)
-            } else {
-                None
-            }
-        }
-        Op::Gtir => {
-            if instr.b < 6 && instr.c < 6 {
-                regs_out[instr.c] = usize::from(instr.a > regs_in[instr.b]);
-                Some(regs_out)
-            } else {
-                None
-            }
-        }
-        Op::Gtri => {
-            if instr.a < 6 && instr.c < 6 {
-                regs_out[instr.c] = usize::from(regs_in[instr.a] > instr.b);
-                Some(regs_out)
-            } else {
-                None
-            }
-        }
-        Op::Gtrr => {
-            if instr.a < 6 && instr.b < 6 && instr.c < 6 {
-                regs_out[instr.c] = usize::from(regs_in[instr.a] > regs_in[instr.b]);
-                Some(regs_out)
-            } else {
-                None
-            }
-        }
-        Op::Eqir => {
-            if instr.b < 6 && instr.c < 6 {
-                regs_out[instr.c] = usize::from(instr.a == regs_in[instr.b]);
-                Some(regs_out)
-            } else {
-                None
-            }
-        }
-        Op::Eqri => {
-            if instr.a < 6 && instr.c < 6 {
-                regs_out[instr.c] = usize::from(regs_in[instr.a] == instr.b);
-                Some(regs_out)
-            } else {
-                None
-            }
-        }
-        Op::Eqrr => {
-            if instr.a < 6 && instr.b < 6 && instr.c < 6 {
-                regs_out[instr.c] = usize::from(regs_in[instr.a] == regs_in[instr.b]);
-                Some(regs_out)
-            } else {
-                None
-            }
-        }
+    regs_out[instr.c] = val;
+    Ok(regs_out)
+}
+
+// Which opcode number is which op, from samples of the operands (a, b, c)
+// and register states before and after.
+pub fn infer_opcodes<const N: usize>(samples: &[Sample<[usize; 3], [usize; N]>]) -> Inference<Op> {
+    opinfer::infer(&Op::ALL, samples, |op, &[a, b, c], before| {
+        exec_op(&Instruction { op: *op, a, b, c }, before).ok()
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PROGRAM: &str = r#"#ip 0
+seti 5 0 1
+seti 6 0 2
+addi 0 1 0
+addr 1 2 3
+setr 1 0 0
+seti 8 0 4
+seti 9 0 5"#;
+
+    #[test]
+    fn test_run() {
+        let mut wrist = Wrist::new_from_str(PROGRAM);
+        wrist.run().unwrap();
+        assert_eq!(wrist.reg, [6, 5, 6, 0, 0, 9]);
+    }
+
+    #[test]
+    fn test_hook() {
+        let mut wrist = Wrist::new_from_str(PROGRAM);
+        let mut visited = vec![];
+        let exit = wrist
+            .run_with(|ip, instr, reg| {
+                visited.push(ip);
+                if instr.op == Op::Setr {
+                    Hook::Stop
+                } else if ip == 1 {
+                    // skip seti 6 0 2 and override register 2 instead
+                    reg[2] = 10;
+                    Hook::Skip
+                } else {
+                    Hook::Continue
+                }
+            })
+            .unwrap();
+        assert_eq!(exit, Exit::Stopped);
+        assert_eq!(visited, vec![0, 1, 2, 4]);
+        assert_eq!(wrist.reg[2], 10);
+        assert_eq!(wrist.ip, 4);
+    }
+
+    #[test]
+    fn test_parse() {
+        let labelled = r#"#ip 0     ; ip bound to register 0
+        seti 5 0 1
+        seti 6 0 2
+        addi 0 1 0
+        addr 1 2 3
+        setr 1 0 0
+skip:   seti 8 0 4
+        seti 9 0 5
+        seti skip 0 0"#;
+        let wrist = Wrist::parse(labelled).unwrap();
+        let text = wrist.to_string();
+        assert_eq!(text, format!("{}\nseti 4 0 0", PROGRAM));
+        assert_eq!(Wrist::parse(&text).unwrap().program, wrist.program);
+        assert_eq!(wrist.ip_reg, 0);
+
+        let error = |input| Wrist::parse(input).err().unwrap().to_string();
+        assert_eq!(
+            error("#ip 1\nseti 1 0 6"),
+            "line 2, column 10: invalid register 6"
+        );
+        assert_eq!(
+            error("#ip 1\n\n jmp 1 0 2"),
+            "line 3, column 2: unknown instruction 'jmp'"
+        );
+        assert_eq!(
+            error("#ip 1\naddi 1 x 2"),
+            "line 2, column 8: expected number or label, found 'x'"
+        );
+        assert_eq!(
+            error("#ip 1\naddr 1 2"),
+            "line 2, column 1: 'addr' expects 3 operands, found 2"
+        );
+        assert_eq!(
+            error("seti 1 0 2"),
+            "line 1, column 1: missing #ip directive"
+        );
+        assert_eq!(
+            error("#ip 1\nstart: seti start 0 1"),
+            "line 2, column 13: label 'start' has no address before it"
+        );
+    }
+
+    #[test]
+    fn test_invalid_register() {
+        let mut wrist = Wrist::parse("#ip 0\nseti 1 0 5").unwrap();
+        wrist.program[0].c = 6;
+        assert_eq!(
+            wrist.run(),
+            Err(WristError::InvalidRegister { ip: 0, reg: 6 })
+        );
     }
 }
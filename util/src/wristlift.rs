@@ -0,0 +1,834 @@
+use crate::wrist::{exec_op, Exit, Hook, Instruction, Op, Registers, Wrist, WristError};
+use std::collections::BTreeSet;
+use std::fmt;
+
+// Lifter for Wrist programs. Writes to the ip-bound register are turned
+// into jumps, which gives a control flow graph of basic blocks and loops
+// that can be printed as pseudo-code. Common idioms (like summing up
+// divisors in two nested loops) are recognized, so that they can be run
+// natively instead of being stepped through.
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Jump {
+    // no write to the ip register
+    Next,
+    // targets beyond the end of the program halt it
+    Static(usize),
+    // ip += flag, where the flag is set by the preceding comparison:
+    // (target if false, target if true)
+    Cond(usize, usize),
+    // target depends on a register value
+    Dynamic,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct BasicBlock {
+    pub start: usize,
+    // exclusive
+    pub end: usize,
+    pub succ: Vec<usize>,
+}
+
+// A loop spans the contiguous range of blocks from its header up to the
+// end of the last block jumping back to it.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct Loop {
+    pub header: usize,
+    pub end: usize,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Idiom {
+    // do { j = 1; do { if i * j == n { acc += i } j += 1 } while j <= n;
+    //      i += 1 } while i <= n
+    DivisorSum {
+        start: usize,
+        end: usize,
+        i: usize,
+        j: usize,
+        n: usize,
+        acc: usize,
+        tmp: usize,
+    },
+    // q = 0; while (q + 1) * d <= x { q += 1 }; goto exit
+    Divide {
+        start: usize,
+        exit: usize,
+        q: usize,
+        x: usize,
+        d: usize,
+        tmp: usize,
+    },
+}
+
+impl Idiom {
+    pub fn start(&self) -> usize {
+        match self {
+            Idiom::DivisorSum { start, .. } | Idiom::Divide { start, .. } => *start,
+        }
+    }
+
+    // Executes the idiom on the registers, returns the ip to continue at.
+    pub fn apply(&self, reg: &mut Registers) -> usize {
+        match *self {
+            Idiom::DivisorSum {
+                end,
+                i,
+                j,
+                n,
+                acc,
+                tmp,
+                ..
+            } => {
+                let (i0, n_val) = (reg[i], reg[n]);
+                reg[acc] += divisor_sum(n_val, i0);
+                reg[i] = i0.max(n_val) + 1;
+                reg[j] = n_val.max(1) + 1;
+                reg[tmp] = 1;
+                end
+            }
+            Idiom::Divide {
+                exit, q, x, d, tmp, ..
+            } => {
+                reg[q] = reg[x] / d;
+                reg[tmp] = 1;
+                exit
+            }
+        }
+    }
+}
+
+// sum of all divisors of n which are >= min
+fn divisor_sum(n: usize, min: usize) -> usize {
+    let mut sum = 0;
+    let mut d = 1;
+    while d * d <= n {
+        if n.is_multiple_of(d) {
+            if d >= min {
+                sum += d;
+            }
+            if d * d != n && n / d >= min {
+                sum += n / d;
+            }
+        }
+        d += 1;
+    }
+    sum
+}
+
+pub struct Lifted<'a> {
+    program: &'a [Instruction],
+    ip_reg: usize,
+    jumps: Vec<Jump>,
+    pub blocks: Vec<BasicBlock>,
+    pub loops: Vec<Loop>,
+    pub idioms: Vec<Idiom>,
+    names: Vec<String>,
+}
+
+impl<'a> Lifted<'a> {
+    pub fn new(wrist: &'a Wrist) -> Lifted<'a> {
+        let program = &wrist.program[..];
+        let ip_reg = wrist.ip_reg;
+        let jumps: Vec<_> = (0..program.len())
+            .map(|i| jump(program, ip_reg, i))
+            .collect();
+        let blocks = blocks(&jumps);
+        let loops = loops(&blocks);
+        let idioms = (0..program.len())
+            .filter_map(|i| match_idiom(program, ip_reg, i))
+            .collect();
+
+        let mut lifted = Lifted {
+            program,
+            ip_reg,
+            jumps,
+            blocks,
+            loops,
+            idioms,
+            names: vec![],
+        };
+        lifted.names = lifted.register_names();
+        lifted
+    }
+
+    pub fn jump(&self, ip: usize) -> Jump {
+        self.jumps[ip]
+    }
+
+    pub fn reg_name(&self, reg: usize) -> &str {
+        &self.names[reg]
+    }
+
+    // The ip register is "ip", loop counters are named i, j, k, ... from
+    // the outermost loop inwards, registers which never hold a value from
+    // one block to another are temporaries t, t2, ...
+    fn register_names(&self) -> Vec<String> {
+        let mut names: Vec<_> = (0..6).map(|r| format!("r{}", r)).collect();
+        names[self.ip_reg] = String::from("ip");
+
+        let depth = |ip: usize| {
+            self.loops
+                .iter()
+                .filter(|l| l.header <= ip && ip < l.end)
+                .count()
+        };
+        let mut counters = vec![];
+        for (ip, instr) in self.program.iter().enumerate() {
+            let is_increment = instr.op == Op::Addi && instr.a == instr.c && instr.b == 1;
+            let compared = self
+                .program
+                .get(ip + 1)
+                .is_some_and(|next| is_comparison(next.op) && reads(next).contains(&instr.c));
+            if is_increment && compared && instr.c != self.ip_reg && depth(ip) > 0 {
+                counters.push((depth(ip), ip, instr.c));
+            }
+        }
+        counters.sort_unstable();
+        let mut counter_names = ["i", "j", "k", "l", "m"].iter();
+        let mut named = BTreeSet::from([self.ip_reg]);
+        for (_, _, r) in counters {
+            if named.insert(r) {
+                if let Some(name) = counter_names.next() {
+                    names[r] = name.to_string();
+                }
+            }
+        }
+
+        let mut live_in = BTreeSet::new();
+        let mut written = BTreeSet::new();
+        for block in &self.blocks {
+            let mut local = BTreeSet::new();
+            for instr in &self.program[block.start..block.end] {
+                live_in.extend(reads(instr).into_iter().filter(|r| !local.contains(r)));
+                local.insert(instr.c);
+                written.insert(instr.c);
+            }
+        }
+        let mut temp_no = 1;
+        for r in written {
+            if r < 6 && !live_in.contains(&r) && !named.contains(&r) {
+                names[r] = match temp_no {
+                    1 => String::from("t"),
+                    _ => format!("t{}", temp_no),
+                };
+                temp_no += 1;
+            }
+        }
+        names
+    }
+
+    fn operand(&self, reg: usize, ip: usize) -> String {
+        if reg == self.ip_reg {
+            ip.to_string()
+        } else {
+            self.names.get(reg).cloned().unwrap_or(format!("r{}", reg))
+        }
+    }
+
+    fn expr(&self, ip: usize) -> String {
+        let instr = &self.program[ip];
+        let a = || self.operand(instr.a, ip);
+        let b = || self.operand(instr.b, ip);
+        match instr.op {
+            Op::Addr => format!("{} + {}", a(), b()),
+            Op::Addi => format!("{} + {}", a(), instr.b),
+            Op::Mulr => format!("{} * {}", a(), b()),
+            Op::Muli => format!("{} * {}", a(), instr.b),
+            Op::Banr => format!("{} & {}", a(), b()),
+            Op::Bani => format!("{} & {}", a(), instr.b),
+            Op::Borr => format!("{} | {}", a(), b()),
+            Op::Bori => format!("{} | {}", a(), instr.b),
+            Op::Setr => a(),
+            Op::Seti => instr.a.to_string(),
+            Op::Gtir => format!("{} > {}", instr.a, b()),
+            Op::Gtri => format!("{} > {}", a(), instr.b),
+            Op::Gtrr => format!("{} > {}", a(), b()),
+            Op::Eqir => format!("{} == {}", instr.a, b()),
+            Op::Eqri => format!("{} == {}", a(), instr.b),
+            Op::Eqrr => format!("{} == {}", a(), b()),
+        }
+    }
+
+    fn assignment(&self, ip: usize) -> String {
+        let instr = &self.program[ip];
+        let dst = &self.names[instr.c];
+        let compound = match instr.op {
+            Op::Addr | Op::Addi => Some("+="),
+            Op::Mulr | Op::Muli => Some("*="),
+            Op::Banr | Op::Bani => Some("&="),
+            Op::Borr | Op::Bori => Some("|="),
+            _ => None,
+        };
+        let reg_b = matches!(instr.op, Op::Addr | Op::Mulr | Op::Banr | Op::Borr);
+        match compound {
+            Some(op) if instr.a == instr.c => {
+                let rhs = if reg_b {
+                    self.operand(instr.b, ip)
+                } else {
+                    instr.b.to_string()
+                };
+                format!("{} {} {};", dst, op, rhs)
+            }
+            Some(op) if reg_b && instr.b == instr.c => {
+                format!("{} {} {};", dst, op, self.operand(instr.a, ip))
+            }
+            _ if is_comparison(instr.op) => format!("{} = ({});", dst, self.expr(ip)),
+            _ => format!("{} = {};", dst, self.expr(ip)),
+        }
+    }
+
+    // innermost loop containing ip
+    fn enclosing_loop(&self, ip: usize) -> Option<&Loop> {
+        self.loops
+            .iter()
+            .filter(|l| l.header <= ip && ip < l.end)
+            .min_by_key(|l| l.end - l.header)
+    }
+
+    fn goto(&self, ip: usize, target: usize, labels: &mut BTreeSet<usize>) -> String {
+        if target >= self.program.len() {
+            return String::from("halt");
+        }
+        match self.enclosing_loop(ip) {
+            Some(l) if l.header == target => String::from("continue"),
+            Some(l) if l.end == target => String::from("break"),
+            _ => {
+                labels.insert(target);
+                format!("goto L{}", target)
+            }
+        }
+    }
+
+    fn statements(&self, block: &BasicBlock, labels: &mut BTreeSet<usize>) -> Vec<String> {
+        let mut lines = vec![];
+        for ip in block.start..block.end {
+            if let Some(idiom) = self.idioms.iter().find(|i| i.start() == ip) {
+                lines.push(format!("// {}", self.describe(idiom)));
+            }
+            match self.jumps[ip] {
+                Jump::Next => {
+                    // comparisons feeding a conditional jump are folded into it
+                    let folded = ip + 1 < block.end
+                        && matches!(self.jumps[ip + 1], Jump::Cond(..))
+                        && is_comparison(self.program[ip].op);
+                    if !folded {
+                        lines.push(self.assignment(ip));
+                    }
+                }
+                Jump::Static(target) => {
+                    lines.push(format!("{};", self.goto(ip, target, labels)));
+                }
+                Jump::Cond(_, target) => {
+                    let cond = if ip > block.start && is_comparison(self.program[ip - 1].op) {
+                        self.expr(ip - 1)
+                    } else {
+                        let instr = &self.program[ip];
+                        let flag = if instr.a == self.ip_reg {
+                            instr.b
+                        } else {
+                            instr.a
+                        };
+                        self.names[flag].clone()
+                    };
+                    lines.push(format!("if ({}) {};", cond, self.goto(ip, target, labels)));
+                }
+                Jump::Dynamic => {
+                    lines.push(format!("jump {} + 1;", self.expr(ip)));
+                }
+            }
+        }
+        lines
+    }
+
+    fn describe(&self, idiom: &Idiom) -> String {
+        let n = |r: usize| &self.names[r];
+        match *idiom {
+            Idiom::DivisorSum { i, n: nr, acc, .. } => format!(
+                "idiom: {} += sum of divisors of {} which are >= {}",
+                n(acc),
+                n(nr),
+                n(i)
+            ),
+            Idiom::Divide { q, x, d, exit, .. } => {
+                format!("idiom: {} = {} / {}, continue at L{}", n(q), n(x), d, exit)
+            }
+        }
+    }
+}
+
+impl fmt::Display for Lifted<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut labels = BTreeSet::new();
+        let statements: Vec<_> = self
+            .blocks
+            .iter()
+            .map(|b| self.statements(b, &mut labels))
+            .collect();
+        // possible targets of dynamic jumps
+        labels.extend(
+            self.blocks
+                .iter()
+                .filter(|b| b.start > 0 && self.jumps[b.start - 1] == Jump::Dynamic)
+                .map(|b| b.start),
+        );
+
+        let mut depth = 0;
+        let indent = |depth: usize| "    ".repeat(depth + 1);
+        writeln!(f, "#ip {}", self.ip_reg)?;
+        for (block, lines) in self.blocks.iter().zip(statements) {
+            for _ in self.loops.iter().filter(|l| l.header == block.start) {
+                writeln!(f, "{}loop {{", indent(depth))?;
+                depth += 1;
+            }
+            if labels.contains(&block.start) {
+                writeln!(f, "{}L{}:", &indent(depth)[4..], block.start)?;
+            }
+            for line in lines {
+                writeln!(f, "{}{}", indent(depth), line)?;
+            }
+            for _ in self.loops.iter().filter(|l| l.end == block.end) {
+                depth -= 1;
+                writeln!(f, "{}}}", indent(depth))?;
+            }
+        }
+        Ok(())
+    }
+}
+
+fn is_comparison(op: Op) -> bool {
+    matches!(
+        op,
+        Op::Gtir | Op::Gtri | Op::Gtrr | Op::Eqir | Op::Eqri | Op::Eqrr
+    )
+}
+
+fn reads(instr: &Instruction) -> Vec<usize> {
+    match instr.op {
+        Op::Addr | Op::Mulr | Op::Banr | Op::Borr | Op::Gtrr | Op::Eqrr => vec![instr.a, instr.b],
+        Op::Addi | Op::Muli | Op::Bani | Op::Bori | Op::Setr | Op::Gtri | Op::Eqri => {
+            vec![instr.a]
+        }
+        Op::Gtir | Op::Eqir => vec![instr.b],
+        Op::Seti => vec![],
+    }
+}
+
+fn jump(program: &[Instruction], ip_reg: usize, ip: usize) -> Jump {
+    let instr = &program[ip];
+    if instr.c != ip_reg {
+        return Jump::Next;
+    }
+    let other: Vec<_> = reads(instr).into_iter().filter(|r| *r != ip_reg).collect();
+    if other.is_empty() {
+        // only depends on the (known) value of ip
+        let mut reg = [0; 6];
+        reg[ip_reg] = ip;
+        return match exec_op(instr, &reg) {
+            Ok(reg) => Jump::Static(reg[ip_reg].saturating_add(1)),
+            Err(_) => Jump::Dynamic,
+        };
+    }
+    let is_flag = ip > 0 && {
+        let prev = &program[ip - 1];
+        is_comparison(prev.op) && prev.c == other[0]
+    };
+    if instr.op == Op::Addr && other.len() == 1 && reads(instr).contains(&ip_reg) && is_flag {
+        Jump::Cond(ip + 1, ip + 2)
+    } else {
+        Jump::Dynamic
+    }
+}
+
+fn blocks(jumps: &[Jump]) -> Vec<BasicBlock> {
+    let len = jumps.len();
+    let mut leaders = BTreeSet::from([0]);
+    for (ip, j) in jumps.iter().enumerate() {
+        match *j {
+            Jump::Next => continue,
+            Jump::Static(t) => {
+                leaders.insert(t);
+            }
+            Jump::Cond(f, t) => {
+                leaders.insert(f);
+                leaders.insert(t);
+            }
+            Jump::Dynamic => {}
+        }
+        leaders.insert(ip + 1);
+    }
+    let leaders: Vec<_> = leaders.into_iter().filter(|l| *l < len).collect();
+
+    let mut blocks = vec![];
+    for (i, &start) in leaders.iter().enumerate() {
+        let end = leaders.get(i + 1).copied().unwrap_or(len);
+        let succ = match jumps[end - 1] {
+            Jump::Next => vec![end],
+            Jump::Static(t) => vec![t],
+            Jump::Cond(f, t) => vec![f, t],
+            Jump::Dynamic => vec![],
+        };
+        let succ = succ.into_iter().filter(|s| *s < len).collect();
+        blocks.push(BasicBlock { start, end, succ });
+    }
+    blocks
+}
+
+// Loops are found by their back edges, i.e. jumps to an earlier block.
+// Loops which are not properly nested in each other are dropped.
+fn loops(blocks: &[BasicBlock]) -> Vec<Loop> {
+    let mut loops: Vec<Loop> = vec![];
+    for block in blocks {
+        for &header in block.succ.iter().filter(|s| **s <= block.start) {
+            match loops.iter_mut().find(|l| l.header == header) {
+                Some(l) => l.end = l.end.max(block.end),
+                None => loops.push(Loop {
+                    header,
+                    end: block.end,
+                }),
+            }
+        }
+    }
+    loops.sort_unstable_by_key(|l| (l.header, std::cmp::Reverse(l.end)));
+
+    let mut nested: Vec<Loop> = vec![];
+    for l in loops {
+        let overlaps = nested.iter().any(|o| l.header < o.end && o.end < l.end);
+        if !overlaps {
+            nested.push(l);
+        }
+    }
+    nested
+}
+
+#[derive(Copy, Clone)]
+enum Arg {
+    Var(usize),
+    Ip,
+    Imm(usize),
+    Any,
+}
+
+type Pattern = [(Op, Arg, Arg, Arg)];
+
+const DIVISOR_SUM: [(Op, Arg, Arg, Arg); 14] = {
+    use Arg::*;
+    let (i, j, t, n, acc, k1, k2) = (Var(0), Var(1), Var(2), Var(3), Var(4), Var(5), Var(6));
+    [
+        (Op::Seti, Imm(1), Any, j),
+        (Op::Mulr, i, j, t),
+        (Op::Eqrr, t, n, t),
+        (Op::Addr, t, Ip, Ip),
+        (Op::Addi, Ip, Imm(1), Ip),
+        (Op::Addr, i, acc, acc),
+        (Op::Addi, j, Imm(1), j),
+        (Op::Gtrr, j, n, t),
+        (Op::Addr, t, Ip, Ip),
+        (Op::Seti, k1, Any, Ip),
+        (Op::Addi, i, Imm(1), i),
+        (Op::Gtrr, i, n, t),
+        (Op::Addr, t, Ip, Ip),
+        (Op::Seti, k2, Any, Ip),
+    ]
+};
+
+const DIVIDE: [(Op, Arg, Arg, Arg); 9] = {
+    use Arg::*;
+    let (q, t, d, x, k1, k2) = (Var(0), Var(1), Var(2), Var(3), Var(4), Var(5));
+    [
+        (Op::Seti, Imm(0), Any, q),
+        (Op::Addi, q, Imm(1), t),
+        (Op::Muli, t, d, t),
+        (Op::Gtrr, t, x, t),
+        (Op::Addr, t, Ip, Ip),
+        (Op::Addi, Ip, Imm(1), Ip),
+        (Op::Seti, k1, Any, Ip),
+        (Op::Addi, q, Imm(1), q),
+        (Op::Seti, k2, Any, Ip),
+    ]
+};
+
+fn match_idiom(program: &[Instruction], ip_reg: usize, start: usize) -> Option<Idiom> {
+    // registers of an idiom must be distinct and not the ip register
+    let distinct = |regs: &[usize]| {
+        regs.iter()
+            .enumerate()
+            .all(|(k, r)| *r < 6 && *r != ip_reg && !regs[..k].contains(r))
+    };
+
+    if let Some(v) = match_pattern(program, ip_reg, start, &DIVISOR_SUM, 0, [None; 8]) {
+        let [i, j, t, n, acc, k1, k2] = [0, 1, 2, 3, 4, 5, 6].map(|k| v[k].unwrap());
+        if k1 == start && k2 + 1 == start && distinct(&[i, j, t, n, acc]) {
+            return Some(Idiom::DivisorSum {
+                start,
+                end: start + DIVISOR_SUM.len(),
+                i,
+                j,
+                n,
+                acc,
+                tmp: t,
+            });
+        }
+    }
+    if let Some(v) = match_pattern(program, ip_reg, start, &DIVIDE, 0, [None; 8]) {
+        let [q, t, d, x, k1, k2] = [0, 1, 2, 3, 4, 5].map(|k| v[k].unwrap());
+        let inside = (start..start + DIVIDE.len()).contains(&(k1 + 1));
+        if k2 == start && d > 0 && !inside && distinct(&[q, t, x]) {
+            return Some(Idiom::Divide {
+                start,
+                exit: k1 + 1,
+                q,
+                x,
+                d,
+                tmp: t,
+            });
+        }
+    }
+    None
+}
+
+// Matches the pattern at program[start + k..], backtracking over the
+// operand order of commutative ops.
+fn match_pattern(
+    program: &[Instruction],
+    ip_reg: usize,
+    start: usize,
+    pattern: &Pattern,
+    k: usize,
+    vars: [Option<usize>; 8],
+) -> Option<[Option<usize>; 8]> {
+    if k == pattern.len() {
+        return Some(vars);
+    }
+    let instr = program.get(start + k)?;
+    let (op, a, b, c) = pattern[k];
+    if instr.op != op {
+        return None;
+    }
+    let commutative = matches!(op, Op::Addr | Op::Mulr | Op::Banr | Op::Borr | Op::Eqrr);
+    let mut orders = vec![(instr.a, instr.b)];
+    if commutative {
+        orders.push((instr.b, instr.a));
+    }
+    for (x, y) in orders {
+        let mut v = vars;
+        if bind(&mut v, a, x, ip_reg)
+            && bind(&mut v, b, y, ip_reg)
+            && bind(&mut v, c, instr.c, ip_reg)
+        {
+            if let Some(v) = match_pattern(program, ip_reg, start, pattern, k + 1, v) {
+                return Some(v);
+            }
+        }
+    }
+    None
+}
+
+fn bind(vars: &mut [Option<usize>; 8], arg: Arg, val: usize, ip_reg: usize) -> bool {
+    match arg {
+        Arg::Var(n) => match vars[n] {
+            Some(v) => v == val,
+            None => {
+                vars[n] = Some(val);
+                true
+            }
+        },
+        Arg::Ip => val == ip_reg,
+        Arg::Imm(v) => val == v,
+        Arg::Any => true,
+    }
+}
+
+// Wraps a hook for Wrist::run_with, so that recognized idioms are executed
+// natively whenever the hook lets the instruction run.
+pub fn accelerate<'a, F>(
+    idioms: &'a [Idiom],
+    ip_reg: usize,
+    mut hook: F,
+) -> impl FnMut(usize, &Instruction, &mut Registers) -> Hook + 'a
+where
+    F: FnMut(usize, &Instruction, &mut Registers) -> Hook + 'a,
+{
+    move |ip, instr, reg| match hook(ip, instr, reg) {
+        Hook::Continue => match idioms.iter().find(|idiom| idiom.start() == ip) {
+            Some(idiom) => {
+                reg[ip_reg] = idiom.apply(reg) - 1;
+                Hook::Skip
+            }
+            None => Hook::Continue,
+        },
+        h => h,
+    }
+}
+
+pub fn run_accelerated(wrist: &mut Wrist, idioms: &[Idiom]) -> Result<Exit, WristError> {
+    let ip_reg = wrist.ip_reg;
+    wrist.run_with(accelerate(idioms, ip_reg, |_, _, _| Hook::Continue))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // sum of the divisors of 10, or of 20 if r0 starts at 1
+    const DIVISORS: &str = r#"#ip 3
+addi 3 16 3
+seti 1 8 1
+seti 1 3 4
+mulr 1 4 2
+eqrr 2 5 2
+addr 2 3 3
+addi 3 1 3
+addr 1 0 0
+addi 4 1 4
+gtrr 4 5 2
+addr 3 2 3
+seti 2 6 3
+addi 1 1 1
+gtrr 1 5 2
+addr 2 3 3
+seti 1 2 3
+mulr 3 3 3
+seti 10 0 5
+addr 3 0 3
+seti 0 0 3
+seti 20 0 5
+seti 0 0 0
+seti 0 0 3"#;
+
+    const DIVIDE_PROGRAM: &str = r#"#ip 4
+seti 1000 0 3
+seti 0 2 5
+addi 5 1 1
+muli 1 256 1
+gtrr 1 3 1
+addr 1 4 4
+addi 4 1 4
+seti 9 5 4
+addi 5 1 5
+seti 1 6 4
+setr 5 3 3"#;
+
+    #[test]
+    fn test_jumps() {
+        let wrist = Wrist::new_from_str(DIVISORS);
+        let lifted = Lifted::new(&wrist);
+        assert_eq!(lifted.jump(0), Jump::Static(17));
+        assert_eq!(lifted.jump(1), Jump::Next);
+        assert_eq!(lifted.jump(5), Jump::Cond(6, 7));
+        assert_eq!(lifted.jump(11), Jump::Static(3));
+        assert_eq!(lifted.jump(16), Jump::Static(257));
+        assert_eq!(lifted.jump(18), Jump::Dynamic);
+    }
+
+    #[test]
+    fn test_cfg() {
+        let wrist = Wrist::new_from_str(DIVISORS);
+        let lifted = Lifted::new(&wrist);
+        let starts: Vec<_> = lifted.blocks.iter().map(|b| b.start).collect();
+        assert_eq!(
+            starts,
+            vec![0, 1, 2, 3, 6, 7, 8, 11, 12, 15, 16, 17, 19, 20]
+        );
+        assert_eq!(
+            lifted.blocks[3],
+            BasicBlock {
+                start: 3,
+                end: 6,
+                succ: vec![6, 7]
+            }
+        );
+        assert_eq!(
+            lifted.loops,
+            vec![
+                Loop { header: 1, end: 23 },
+                Loop { header: 2, end: 16 },
+                Loop { header: 3, end: 12 },
+            ]
+        );
+        assert_eq!(lifted.reg_name(1), "i");
+        assert_eq!(lifted.reg_name(3), "ip");
+        assert_eq!(lifted.reg_name(4), "j");
+    }
+
+    #[test]
+    fn test_pseudo_code() {
+        let wrist = Wrist::new_from_str(DIVISORS);
+        let code = Lifted::new(&wrist).to_string();
+        let expected = r#"
+            loop {
+                t = i * j;
+                if (t == r5) goto L7;
+                goto L8;
+            L7:
+                r0 += i;
+            L8:
+                j += 1;
+                if (j > r5) break;
+                continue;
+            }
+            i += 1;
+            if (i > r5) break;
+            continue;
+        }
+        halt;
+    L17:
+"#;
+        assert!(code.contains(expected), "{}", code);
+        assert!(code.contains("// idiom: r0 += sum of divisors of r5 which are >= i"));
+        assert!(code.contains("jump 18 + r0 + 1;\n    L19:"));
+    }
+
+    #[test]
+    fn test_divisor_sum() {
+        let mut wrist = Wrist::new_from_str(DIVISORS);
+        let idioms = Lifted::new(&wrist).idioms;
+        assert_eq!(
+            idioms,
+            vec![Idiom::DivisorSum {
+                start: 2,
+                end: 16,
+                i: 1,
+                j: 4,
+                n: 5,
+                acc: 0,
+                tmp: 2
+            }]
+        );
+
+        run_accelerated(&mut wrist, &idioms).unwrap();
+        let mut plain = Wrist::new_from_str(DIVISORS);
+        plain.run().unwrap();
+        assert_eq!(wrist.reg, plain.reg);
+        assert_eq!(wrist.reg[0], 18);
+
+        let mut wrist = Wrist::new_from_str(DIVISORS);
+        wrist.reg[0] = 1;
+        run_accelerated(&mut wrist, &idioms).unwrap();
+        assert_eq!(wrist.reg[0], divisor_sum(20, 1));
+        assert_eq!(wrist.reg[0], 42);
+    }
+
+    #[test]
+    fn test_divide() {
+        let mut wrist = Wrist::new_from_str(DIVIDE_PROGRAM);
+        let idioms = Lifted::new(&wrist).idioms;
+        assert_eq!(
+            idioms,
+            vec![Idiom::Divide {
+                start: 1,
+                exit: 10,
+                q: 5,
+                x: 3,
+                d: 256,
+                tmp: 1
+            }]
+        );
+
+        run_accelerated(&mut wrist, &idioms).unwrap();
+        let mut plain = Wrist::new_from_str(DIVIDE_PROGRAM);
+        plain.run().unwrap();
+        assert_eq!(wrist.reg, plain.reg);
+        assert_eq!(wrist.reg[3], 3);
+    }
+}
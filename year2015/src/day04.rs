@@ -1,47 +1,22 @@
 use common::day::Day;
-use md5::{Digest, Md5};
+use util::md5search::{leading_zeros, Search};
 
 pub struct Day04 {}
 
-fn find_hash_star1(input: &str) -> usize {
-    let mut i = 1;
-    let mut hasher = Md5::new();
-    loop {
-        hasher.update(input.as_bytes());
-        hasher.update(i.to_string().as_bytes());
-        let result = hasher.finalize_reset();
-        let start = result[0] as usize + result[1] as usize + (result[2] >> 4) as usize;
-        if start == 0 {
-            break;
-        }
-        i += 1
-    }
-    i
-}
-
-fn find_hash_star2(input: &str) -> usize {
-    let mut i = 1;
-    let mut hasher = Md5::new();
-    loop {
-        hasher.update(input.as_bytes());
-        hasher.update(i.to_string().as_bytes());
-        let result = hasher.finalize_reset();
-        let start = result[0] as usize + result[1] as usize + result[2] as usize;
-        if start == 0 {
-            break;
-        }
-        i += 1
-    }
-    i
+fn find_hash(input: &str, zeros: usize) -> u64 {
+    Search::new(input.trim())
+        .start(1)
+        .first(|digest| leading_zeros(digest) >= zeros)
+        .0
 }
 
 impl Day for Day04 {
     fn star1(&self, input: &str) -> String {
-        format!("{}", find_hash_star1(input))
+        format!("{}", find_hash(input, 5))
     }
 
     fn star2(&self, input: &str) -> String {
-        format!("{}", find_hash_star2(input))
+        format!("{}", find_hash(input, 6))
     }
 }
 
@@ -1,8 +1,9 @@
 use common::day::Day;
-use std::collections::HashMap;
+use util::expr::{Circuit, Node, Operand, Operator};
 
 pub struct Day07 {}
 
+#[derive(Copy, Clone)]
 enum Op {
     Nop,
     Not,
@@ -12,93 +13,53 @@ enum Op {
     Rshift,
 }
 
-enum Input<'a> {
-    Value(u16),
-    Gate(&'a str),
-}
-
-struct Gate<'a> {
-    inputs: Vec<Input<'a>>,
-    op: Op,
-}
-
-type Gates<'a> = HashMap<&'a str, Gate<'a>>;
-type Results = HashMap<String, u16>;
-
-fn eval(gates: &Gates, results: &mut Results, id: &str) -> u16 {
-    if let Some(result) = results.get(id) {
-        return *result;
+impl Operator<u16> for Op {
+    fn apply(&self, in_val: &[u16]) -> u16 {
+        match self {
+            Op::Nop => in_val[0],
+            Op::Not => !in_val[0],
+            Op::And => in_val[0] & in_val[1],
+            Op::Or => in_val[0] | in_val[1],
+            Op::Lshift => in_val[0] << in_val[1],
+            Op::Rshift => in_val[0] >> in_val[1],
+        }
     }
-
-    let gate = gates.get(id).unwrap();
-    let in_val: Vec<_> = gate
-        .inputs
-        .iter()
-        .map(|i| match i {
-            Input::Value(x) => *x,
-            Input::Gate(id) => eval(gates, results, id),
-        })
-        .collect();
-
-    let result = match gate.op {
-        Op::Nop => in_val[0],
-        Op::Not => !in_val[0],
-        Op::And => in_val[0] & in_val[1],
-        Op::Or => in_val[0] | in_val[1],
-        Op::Lshift => in_val[0] << in_val[1],
-        Op::Rshift => in_val[0] >> in_val[1],
-    };
-    results.insert(String::from(id), result);
-    result
 }
 
-fn parse_input_val(s: &str) -> Input<'_> {
+type Gates<'a> = Circuit<&'a str, u16, Op>;
+
+fn parse_input_val(s: &str) -> Operand<&str, u16> {
     if let Ok(v) = s.parse::<u16>() {
-        Input::Value(v)
+        Operand::Const(v)
     } else {
-        Input::Gate(s)
+        Operand::Ref(s)
     }
 }
 
 fn parse_input(input: &str) -> Gates<'_> {
-    let mut gates = HashMap::new();
+    let mut gates = Circuit::new();
     for line in input.lines() {
         let mut it = line.split(" -> ");
         let input_str = it.next().unwrap();
         let id = it.next().unwrap();
 
         let inputs: Vec<_> = input_str.split(' ').collect();
-        match inputs.len() {
+        let node = match inputs.len() {
             1 => {
                 // NOP
-                let input_vals = vec![parse_input_val(inputs[0])];
-                gates.insert(
-                    id,
-                    Gate {
-                        op: Op::Nop,
-                        inputs: input_vals,
-                    },
-                );
+                Node::Op(Op::Nop, vec![parse_input_val(inputs[0])])
             }
             2 => {
                 // NOT
-                let input_vals = vec![parse_input_val(inputs[1])];
                 let op = match inputs[0] {
                     "NOT" => Op::Not,
                     _ => {
                         panic!("cannot parse input string: {}", input_str);
                     }
                 };
-                gates.insert(
-                    id,
-                    Gate {
-                        op,
-                        inputs: input_vals,
-                    },
-                );
+                Node::Op(op, vec![parse_input_val(inputs[1])])
             }
             3 => {
-                let input_vals = vec![parse_input_val(inputs[0]), parse_input_val(inputs[2])];
                 let op = match inputs[1] {
                     "AND" => Op::And,
                     "OR" => Op::Or,
@@ -108,18 +69,16 @@ fn parse_input(input: &str) -> Gates<'_> {
                         panic!("cannot parse input string: {}", input_str);
                     }
                 };
-                gates.insert(
-                    id,
-                    Gate {
-                        op,
-                        inputs: input_vals,
-                    },
-                );
+                Node::Op(
+                    op,
+                    vec![parse_input_val(inputs[0]), parse_input_val(inputs[2])],
+                )
             }
             _ => {
                 panic!("cannot parse input string: {}", input_str);
             }
-        }
+        };
+        gates.insert(id, node);
     }
     gates
 }
@@ -127,27 +86,21 @@ fn parse_input(input: &str) -> Gates<'_> {
 impl Day for Day07 {
     fn star1(&self, input: &str) -> String {
         let gates = parse_input(input);
-        let mut results = HashMap::new();
-        format!("{}", eval(&gates, &mut results, "a"))
+        format!("{}", gates.eval(&"a").unwrap())
     }
 
     fn star2(&self, input: &str) -> String {
         let val_part1 = self.star1(input).parse::<u16>().unwrap();
         let mut gates = parse_input(input);
-        gates.entry("b").and_modify(|e| {
-            *e = Gate {
-                inputs: vec![Input::Value(val_part1)],
-                op: Op::Nop,
-            }
-        });
-        let mut results = HashMap::new();
-        format!("{}", eval(&gates, &mut results, "a"))
+        gates.insert("b", Node::Value(val_part1));
+        format!("{}", gates.eval(&"a").unwrap())
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use std::collections::HashMap;
 
     #[test]
     fn ex1() {
@@ -161,13 +114,13 @@ NOT x -> h
 NOT y -> i";
         let gates = parse_input(input);
         let mut results = HashMap::new();
-        assert_eq!(eval(&gates, &mut results, "d"), 72);
-        assert_eq!(eval(&gates, &mut results, "e"), 507);
-        assert_eq!(eval(&gates, &mut results, "f"), 492);
-        assert_eq!(eval(&gates, &mut results, "g"), 114);
-        assert_eq!(eval(&gates, &mut results, "h"), 65412);
-        assert_eq!(eval(&gates, &mut results, "i"), 65079);
-        assert_eq!(eval(&gates, &mut results, "x"), 123);
-        assert_eq!(eval(&gates, &mut results, "y"), 456);
+        assert_eq!(gates.eval_with(&"d", &mut results).unwrap(), 72);
+        assert_eq!(gates.eval_with(&"e", &mut results).unwrap(), 507);
+        assert_eq!(gates.eval_with(&"f", &mut results).unwrap(), 492);
+        assert_eq!(gates.eval_with(&"g", &mut results).unwrap(), 114);
+        assert_eq!(gates.eval_with(&"h", &mut results).unwrap(), 65412);
+        assert_eq!(gates.eval_with(&"i", &mut results).unwrap(), 65079);
+        assert_eq!(gates.eval_with(&"x", &mut results).unwrap(), 123);
+        assert_eq!(gates.eval_with(&"y", &mut results).unwrap(), 456);
     }
 }
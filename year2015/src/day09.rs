@@ -1,6 +1,7 @@
 use common::day::Day;
 use itertools::Itertools;
-use std::collections::{HashMap, HashSet};
+use std::collections::HashMap;
+use util::combos::permutations;
 
 pub struct Day09 {}
 
@@ -19,33 +20,37 @@ fn parse_input(input: &str) -> Distances<'_> {
         .collect()
 }
 
+fn locations<'a>(distances: &Distances<'a>) -> Vec<&'a str> {
+    distances
+        .keys()
+        .flat_map(|(a, b)| [*a, *b])
+        .sorted()
+        .dedup()
+        .collect()
+}
+
+fn route_length(distances: &Distances, route: &[&str]) -> usize {
+    route
+        .windows(2)
+        .map(|places| distances[&(places[0].min(places[1]), places[0].max(places[1]))])
+        .sum()
+}
+
 fn find_shortest(distances: &Distances) -> usize {
-    let locations1: HashSet<_> = distances.keys().map(|x| x.0).collect();
-    let locations2: HashSet<_> = distances.keys().map(|x| x.1).collect();
-    let locations: HashSet<_> = locations1.union(&locations2).collect();
+    let locations = locations(distances);
     let mut best_dist = usize::MAX;
-    for perm in locations.iter().permutations(locations.len()) {
-        let mut dist = 0;
-        for places in perm.windows(2) {
-            let places: Vec<_> = places.iter().sorted().collect();
-            dist += distances[&(***places[0], ***places[1])];
-        }
+    for perm in permutations(locations) {
+        let dist = route_length(distances, &perm);
         best_dist = best_dist.min(dist);
     }
     best_dist
 }
 
 fn find_longest(distances: &Distances) -> usize {
-    let locations1: HashSet<_> = distances.keys().map(|x| x.0).collect();
-    let locations2: HashSet<_> = distances.keys().map(|x| x.1).collect();
-    let locations: HashSet<_> = locations1.union(&locations2).collect();
+    let locations = locations(distances);
     let mut best_dist = usize::MIN;
-    for perm in locations.iter().permutations(locations.len()) {
-        let mut dist = 0;
-        for places in perm.windows(2) {
-            let places: Vec<_> = places.iter().sorted().collect();
-            dist += distances[&(***places[0], ***places[1])];
-        }
+    for perm in permutations(locations) {
+        let dist = route_length(distances, &perm);
         best_dist = best_dist.max(dist);
     }
     best_dist
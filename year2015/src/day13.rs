@@ -1,6 +1,6 @@
 use common::day::Day;
-use itertools::Itertools;
 use std::collections::{HashMap, HashSet};
+use util::combos::permutations;
 
 pub struct Day13 {}
 
@@ -34,14 +34,13 @@ fn best_happiness(relations: &Relations) -> i64 {
     persons.remove(first_person);
 
     let mut best_happiness = i64::MIN;
-    for mut seating in persons.iter().permutations(num_persons - 1) {
-        seating.push(&first_person);
+    for mut seating in permutations(persons.into_iter().collect()) {
+        seating.push(first_person);
         let mut happiness = 0;
         for (i, seat) in seating.iter().enumerate() {
             let neighbor_left = seating[(i + num_persons - 1) % num_persons];
             let neighbor_right = seating[(i + num_persons + 1) % num_persons];
-            happiness +=
-                relations[&(**seat, *neighbor_left)] + relations[&(**seat, *neighbor_right)];
+            happiness += relations[&(*seat, neighbor_left)] + relations[&(*seat, neighbor_right)];
         }
         best_happiness = best_happiness.max(happiness);
     }
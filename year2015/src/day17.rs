@@ -1,34 +1,33 @@
 use common::day::Day;
-use itertools::Itertools;
-use util::combos::get_combos;
+use util::combos::{count_subset_sums, count_subset_sums_by_size};
 
 pub struct Day17 {}
 
 impl Day for Day17 {
     fn star1(&self, input: &str) -> String {
-        let containers: Vec<_> = input
-            .lines()
-            .map(|l| l.parse::<usize>().unwrap())
-            .sorted()
-            .rev()
-            .collect();
-        format!("{}", get_combos(150, &containers).len())
+        let containers = parse_input(input);
+        format!("{}", count_subset_sums(&containers, 150))
     }
 
     fn star2(&self, input: &str) -> String {
-        let containers: Vec<_> = input
-            .lines()
-            .map(|l| l.parse::<usize>().unwrap())
-            .sorted()
-            .rev()
-            .collect();
-        let combos = get_combos(150, &containers);
-        let min_len = combos.iter().map(|combo| combo.len()).min().unwrap();
-        let min_combos = combos.iter().filter(|combo| combo.len() == min_len).count();
-        format!("{}", min_combos)
+        let containers = parse_input(input);
+        format!("{}", min_combos(&containers, 150))
     }
 }
 
+fn parse_input(input: &str) -> Vec<usize> {
+    input.lines().map(|l| l.parse::<usize>().unwrap()).collect()
+}
+
+// number of combinations with the minimal number of containers
+fn min_combos(containers: &[usize], target: usize) -> u64 {
+    count_subset_sums_by_size(containers, target)
+        .into_iter()
+        .skip(1)
+        .find(|count| *count > 0)
+        .unwrap_or(0)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -36,11 +35,7 @@ mod tests {
     #[test]
     fn ex1() {
         let containers = [20, 15, 10, 5, 5];
-        assert_eq!(get_combos(25, &containers).len(), 4);
-
-        let combos = get_combos(25, &containers);
-        let min_len = combos.iter().map(|combo| combo.len()).min().unwrap();
-        let min_combos = combos.iter().filter(|combo| combo.len() == min_len).count();
-        assert_eq!(min_combos, 3);
+        assert_eq!(count_subset_sums(&containers, 25), 4);
+        assert_eq!(min_combos(&containers, 25), 3);
     }
 }
@@ -101,10 +101,8 @@ fn do_effects(active_effects: &mut Vec<(Action, usize)>, state: &mut GameState)
     for effect in active_effects.iter_mut() {
         effect.1 -= 1;
         match effect.0 {
-            Action::Shield => {
-                if effect.1 == 0 {
-                    stop_shield(state);
-                }
+            Action::Shield if effect.1 == 0 => {
+                stop_shield(state);
             }
             Action::Poison => {
                 do_poison(state);
@@ -1,22 +1,30 @@
 use common::day::Day;
-use util::combos::get_combos;
+use util::combos::{combinations_where, count_subset_sums_by_size, equal_partitions};
 
 pub struct Day24 {}
 
 fn min_qe(packages: &[usize], divisor: usize) -> usize {
     let target = packages.iter().sum::<usize>() / divisor;
-    let combos = get_combos(target, packages);
-    let min_len = combos.iter().map(|combo| combo.len()).min().unwrap();
-    let combos_min_len: Vec<_> = combos
-        .iter()
-        .filter(|combo| combo.len() == min_len)
-        .collect();
-    let min_qe = combos_min_len
-        .iter()
-        .map(|combo| combo.iter().product::<usize>())
-        .min()
-        .unwrap();
-    min_qe
+    let sum = |combo: &[usize]| combo.iter().map(|i| packages[*i]).sum::<usize>();
+    let counts = count_subset_sums_by_size(packages, target);
+    for (size, _) in counts.iter().enumerate().skip(1).filter(|(_, c)| **c > 0) {
+        // candidates for the first group, smallest quantum entanglement first
+        let mut groups: Vec<_> = combinations_where(packages.len(), size, |c| sum(c) <= target)
+            .filter(|c| sum(c) == target)
+            .map(|c| (c.iter().map(|i| packages[*i]).product::<usize>(), c))
+            .collect();
+        groups.sort();
+        for (qe, group) in groups {
+            let rest: Vec<_> = (0..packages.len())
+                .filter(|i| !group.contains(i))
+                .map(|i| packages[i])
+                .collect();
+            if equal_partitions(&rest, divisor - 1).next().is_some() {
+                return qe;
+            }
+        }
+    }
+    panic!("no valid arrangement");
 }
 
 impl Day for Day24 {
@@ -0,0 +1,8 @@
+use common::year::Year;
+use std::process::ExitCode;
+use year2015::Year2015;
+
+// Runs the fixtures in examples/year2015.
+fn main() -> ExitCode {
+    common::fixture::harness(2015, |day_no| Year2015 {}.get_day(day_no))
+}
@@ -1,7 +1,6 @@
 use common::day::Day;
 use regex::Regex;
-use std::cmp::Ordering;
-use util::chardistrib::char_distribution;
+use util::counter::Counter;
 
 pub struct Day04 {}
 
@@ -12,14 +11,8 @@ struct Room<'a> {
 }
 
 fn calc_checksum(s: &str) -> String {
-    let freq = char_distribution(&s.replace('-', ""));
-    let mut freq: Vec<(_, _)> = freq.iter().collect();
-    freq.sort_by(|a, b| match a.1.cmp(b.1) {
-        Ordering::Less => Ordering::Greater,
-        Ordering::Greater => Ordering::Less,
-        Ordering::Equal => a.0.cmp(b.0),
-    });
-    freq[0..5].iter().map(|x| x.0).collect()
+    let freq: Counter<_> = s.chars().filter(|c| *c != '-').collect();
+    freq.most_common().iter().take(5).map(|x| x.0).collect()
 }
 
 fn parse_input(input: &str) -> Vec<Room<'_>> {
@@ -1,44 +1,30 @@
 use common::day::Day;
-use md5::{Digest, Md5};
+use util::md5search::{leading_zeros, nibble, Search};
 
 pub struct Day05 {}
 
+fn hex_char(n: u8) -> char {
+    char::from_digit(n as u32, 16).unwrap()
+}
+
 fn password_part1(prefix: &str) -> String {
-    let mut i = 0;
-    let mut pw = String::new();
-    let mut hasher = Md5::new();
-    while pw.len() < 8 {
-        hasher.update(prefix.as_bytes());
-        hasher.update(i.to_string().as_bytes());
-        let result = hasher.finalize_reset();
-        let start = result[0] as usize + result[1] as usize + (result[2] >> 4) as usize;
-        if start == 0 {
-            pw += &format!("{:x}", result[2] & 0x0f);
-        }
-        i += 1;
-    }
-    pw
+    Search::new(prefix)
+        .matches(|digest| leading_zeros(digest) >= 5)
+        .take(8)
+        .map(|(_, digest)| hex_char(nibble(&digest, 5)))
+        .collect()
 }
 
 fn password_part2(prefix: &str) -> String {
-    let mut i = 0;
     let mut pw: [Option<char>; 8] = [None; 8];
-    let mut hasher = Md5::new();
-    loop {
-        hasher.update(prefix.as_bytes());
-        hasher.update(i.to_string().as_bytes());
-        let result = hasher.finalize_reset();
-        let start = result[0] as usize + result[1] as usize + (result[2] >> 4) as usize;
-        if start == 0 {
-            let pos = (result[2] & 0xf) as usize;
-            if pos < 8 && pw[pos].is_none() {
-                pw[pos] = Some(format!("{:x}", result[3] >> 4).chars().next().unwrap());
-                if pw.iter().all(|x| x.is_some()) {
-                    break;
-                }
+    for (_, digest) in Search::new(prefix).matches(|digest| leading_zeros(digest) >= 5) {
+        let pos = nibble(&digest, 5) as usize;
+        if pos < 8 && pw[pos].is_none() {
+            pw[pos] = Some(hex_char(nibble(&digest, 6)));
+            if pw.iter().all(|x| x.is_some()) {
+                break;
             }
         }
-        i += 1;
     }
     pw.iter().filter_map(|x| *x).collect()
 }
@@ -1,5 +1,5 @@
 use common::day::Day;
-use std::collections::HashMap;
+use util::counter::Counter;
 
 pub struct Day06 {}
 
@@ -11,13 +11,8 @@ fn msg_col(msgs: &[Vec<char>], n: usize) -> Vec<char> {
     msgs.iter().map(|msg| msg[n]).collect()
 }
 
-fn letter_frequency(chars: &[char]) -> HashMap<char, usize> {
-    let mut freq = HashMap::new();
-    for c in chars {
-        let e = freq.entry(*c).or_insert(0);
-        *e += 1;
-    }
-    freq
+fn letter_frequency(chars: &[char]) -> Counter<char> {
+    chars.iter().copied().collect()
 }
 
 impl Day for Day06 {
@@ -26,8 +21,7 @@ impl Day for Day06 {
         let mut s = String::new();
         for col in 0..msgs[0].len() {
             let freq = letter_frequency(&msg_col(&msgs, col));
-            let most_common = freq.iter().max_by(|(_, a), (_, b)| a.cmp(b)).unwrap().0;
-            s.push(*most_common);
+            s.push(*freq.most().unwrap());
         }
         s
     }
@@ -37,8 +31,7 @@ impl Day for Day06 {
         let mut s = String::new();
         for col in 0..msgs[0].len() {
             let freq = letter_frequency(&msg_col(&msgs, col));
-            let most_common = freq.iter().min_by(|(_, a), (_, b)| a.cmp(b)).unwrap().0;
-            s.push(*most_common);
+            s.push(*freq.least().unwrap());
         }
         s
     }
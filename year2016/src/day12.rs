@@ -6,6 +6,7 @@ pub struct Day12 {}
 impl Day for Day12 {
     fn star1(&self, input: &str) -> String {
         let mut computer = Computer::new(input);
+        computer.optimize();
         computer.exec(None, None);
         format!("{}", computer.get_reg(RegId::A))
     }
@@ -13,6 +14,7 @@ impl Day for Day12 {
     fn star2(&self, input: &str) -> String {
         let mut computer = Computer::new(input);
         computer.set_reg(RegId::C, 1);
+        computer.optimize();
         computer.exec(None, None);
         format!("{}", computer.get_reg(RegId::A))
     }
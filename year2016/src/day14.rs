@@ -1,76 +1,49 @@
 use common::day::Day;
-use md5::{Digest, Md5};
-use std::collections::BTreeMap;
+use util::md5search::{hex, Stretched};
 
 pub struct Day14 {}
 
-fn key_index(salt: &str, n: usize, stretch_factor: usize) -> usize {
-    let mut i: usize = 0;
-    let mut keys = vec![];
-    let mut candidates: BTreeMap<usize, char> = BTreeMap::new();
-    let mut hasher = Md5::new();
-
-    while keys.len() < n || !candidates.is_empty() {
-        hasher.update(salt.as_bytes());
-        hasher.update(i.to_string().as_bytes());
-        let result = hasher.finalize_reset();
-        let mut result_str = format!("{:32x}", result);
-
-        for _ in 0..stretch_factor {
-            hasher.update(result_str.as_bytes());
-            let result_stretch = hasher.finalize_reset();
-            result_str = format!("{:32x}", result_stretch);
-        }
-        let result_chars: Vec<_> = result_str.chars().collect();
-
-        // Prune old candidates (more than 1000 away)
-        let mut to_remove = vec![];
-        for k in candidates.keys() {
-            if k + 1000 < i {
-                to_remove.push(*k);
-            } else {
-                break;
-            }
-        }
-        for k in to_remove {
-            candidates.remove(&k);
-        }
-
-        // Check if current key can confirm a key candidate. Requirements:
-        // - quintuple in current hash
-        // - same character in candidates (triples)
-        let mut found_indexes = vec![];
-        if let Some(w) = result_chars
-            .windows(5)
-            .find(|w| w[0] == w[1] && w[0] == w[2] && w[0] == w[3] && w[0] == w[4])
-        {
-            for (cand_index, repeat_char) in candidates.iter() {
-                if *repeat_char == w[0] {
-                    keys.push(*cand_index);
-                    found_indexes.push(*cand_index);
-                }
-            }
-        }
-        for found_index in found_indexes {
-            candidates.remove(&found_index);
-        }
+// For each index the character of the first triple, and a bit mask of the
+// characters occurring five times in a row.
+struct Keys {
+    hashes: Stretched,
+    runs: Vec<(Option<u8>, u16)>,
+}
 
-        // Check for new candidates (triple), but only if we have not yet found enough keys.
-        // Otherwise we just wait for the rest of candidates to resolve, so we can get the final order.
-        if keys.len() < n {
-            if let Some(w) = result_chars
+impl Keys {
+    fn runs(&mut self, idx: usize) -> (Option<u8>, u16) {
+        while self.runs.len() <= idx {
+            let digest = self.hashes.get(self.runs.len());
+            let mut buf = [0; 32];
+            let chars = hex(&digest, &mut buf).as_bytes();
+            let digit = |c: u8| (c as char).to_digit(16).unwrap();
+            let triple = chars
                 .windows(3)
                 .find(|w| w[0] == w[1] && w[0] == w[2])
-            {
-                candidates.insert(i, w[0]);
-            }
+                .map(|w| digit(w[0]) as u8);
+            let quintuples = chars
+                .windows(5)
+                .filter(|w| w.iter().all(|c| *c == w[0]))
+                .fold(0, |mask, w| mask | 1 << digit(w[0]));
+            self.runs.push((triple, quintuples));
         }
+        self.runs[idx]
+    }
 
-        i += 1;
+    fn is_key(&mut self, idx: usize) -> bool {
+        match self.runs(idx).0 {
+            Some(c) => (idx + 1..=idx + 1000).any(|i| self.runs(i).1 & (1 << c) != 0),
+            None => false,
+        }
     }
+}
 
-    keys.sort_unstable();
-    keys[n - 1]
+fn key_index(salt: &str, n: usize, stretch_factor: usize) -> usize {
+    let mut keys = Keys {
+        hashes: Stretched::new(salt, stretch_factor),
+        runs: vec![],
+    };
+    (0..).filter(|i| keys.is_key(*i)).nth(n - 1).unwrap()
 }
 
 impl Day for Day14 {
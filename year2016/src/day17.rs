@@ -1,6 +1,6 @@
 use common::day::Day;
-use md5::{Digest, Md5};
 use std::collections::VecDeque;
+use util::md5search::{nibble, Salted};
 
 pub struct Day17 {}
 
@@ -10,15 +10,12 @@ struct State {
     steps: String,
 }
 
-fn neighbors(current: &State, salt: &str) -> Vec<State> {
-    let mut hasher = Md5::new();
-    hasher.update(salt.as_bytes());
-    hasher.update(current.steps.as_bytes());
-    let hash = hasher.finalize_reset();
-    let open_up = (hash[0] >> 4) >= 0xb;
-    let open_down = (hash[0] & 0x0f) >= 0xb;
-    let open_left = (hash[1] >> 4) >= 0xb;
-    let open_right = (hash[1] & 0x0f) >= 0xb;
+fn neighbors(current: &State, salted: &Salted) -> Vec<State> {
+    let hash = salted.hash(current.steps.as_bytes());
+    let open_up = nibble(&hash, 0) >= 0xb;
+    let open_down = nibble(&hash, 1) >= 0xb;
+    let open_left = nibble(&hash, 2) >= 0xb;
+    let open_right = nibble(&hash, 3) >= 0xb;
 
     let mut neighbors = vec![];
     if current.pos.1 > 0 && open_up {
@@ -49,6 +46,7 @@ fn neighbors(current: &State, salt: &str) -> Vec<State> {
 }
 
 fn search(salt: &str, early_exit: bool) -> Option<String> {
+    let salted = Salted::new(salt);
     let mut frontier = VecDeque::new();
     let mut solutions = vec![];
     let init_pos = State {
@@ -67,7 +65,7 @@ fn search(salt: &str, early_exit: bool) -> Option<String> {
                 solutions.push(current.steps);
             }
         } else {
-            for next in neighbors(&current, salt) {
+            for next in neighbors(&current, &salted) {
                 frontier.push_back(next);
             }
         }
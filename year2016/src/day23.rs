@@ -7,6 +7,7 @@ impl Day for Day23 {
     fn star1(&self, input: &str) -> String {
         let mut computer = Computer::new(input);
         computer.set_reg(RegId::A, 7);
+        computer.optimize();
         computer.exec(None, None);
         format!("{}", computer.get_reg(RegId::A))
     }
@@ -14,6 +15,7 @@ impl Day for Day23 {
     fn star2(&self, input: &str) -> String {
         let mut computer = Computer::new(input);
         computer.set_reg(RegId::A, 12);
+        computer.optimize();
         computer.exec(None, None);
         format!("{}", computer.get_reg(RegId::A))
     }
@@ -1,17 +1,36 @@
 use common::day::Day;
-use util::assembunny::{Computer, RegId};
+use util::assembunny::{Computer, RegId, Signal};
 
 pub struct Day25 {}
 
+// The output is 0, 1, 0, 1, ... forever if prefix and one cycle alternate
+// and the cycle has even length.
+fn is_clock(signal: &Signal) -> bool {
+    match signal {
+        Signal::Periodic { prefix, cycle } => {
+            cycle.len() % 2 == 0
+                && prefix
+                    .iter()
+                    .chain(cycle.iter())
+                    .enumerate()
+                    .all(|(i, v)| *v == (i % 2) as i64)
+        }
+        Signal::Halted(_) => false,
+        // only with an op limit
+        Signal::Unknown(_) => panic!("clock signal not determined"),
+    }
+}
+
 impl Day for Day25 {
     fn star1(&self, input: &str) -> String {
         let mut i = 0;
-        let target = "01".repeat(50);
         loop {
             let mut computer = Computer::new(input);
             computer.set_reg(RegId::A, i);
-            let output = computer.exec(Some(100), Some(1_000_000));
-            if output == target {
+            computer.optimize();
+            // the program prints the bits of a number derived from a over
+            // and over, so the state is bound to repeat
+            if is_clock(&computer.clock_signal(None)) {
                 break;
             }
             i += 1;
@@ -22,4 +41,50 @@ impl Day for Day25 {
     fn star2(&self, _input: &str) -> String {
         String::from("not implemented")
     }
+
+    fn stars(&self) -> usize {
+        1
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ex1() {
+        let d = Day25 {};
+        let input = r#"cpy a d
+cpy 4 c
+cpy 633 b
+inc d
+dec b
+jnz b -2
+dec c
+jnz c -5
+cpy d a
+jnz 0 0
+cpy a b
+cpy 0 a
+cpy 2 c
+jnz b 2
+jnz 1 6
+dec b
+dec c
+jnz c -4
+inc a
+jnz 1 -7
+cpy 2 b
+jnz c 2
+jnz 1 4
+dec b
+dec c
+jnz 1 -4
+jnz 0 0
+out b
+jnz a -19
+jnz 1 -21"#;
+        // a + 4 * 633 = 0b101010101010
+        assert_eq!(d.star1(input), "198");
+    }
 }
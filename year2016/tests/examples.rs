@@ -0,0 +1,8 @@
+use common::year::Year;
+use std::process::ExitCode;
+use year2016::Year2016;
+
+// Runs the fixtures in examples/year2016.
+fn main() -> ExitCode {
+    common::fixture::harness(2016, |day_no| Year2016 {}.get_day(day_no))
+}
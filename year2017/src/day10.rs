@@ -1,5 +1,5 @@
 use common::day::Day;
-use util::knothash::{knot, KnotHash};
+use util::knothash::{KnotHash, KnotHasher, Params};
 
 pub struct Day10 {}
 
@@ -10,9 +10,10 @@ impl Day for Day10 {
             .split(',')
             .map(|x| x.parse::<usize>().unwrap())
             .collect();
-        let mut v: Vec<_> = (0..=255).collect();
-        knot(&mut v, &lengths, 0, 0);
-        format!("{}", v[0] as usize * v[1] as usize)
+        let hash = KnotHasher::new(Params::single_round(256))
+            .update_lengths(&lengths)
+            .finish();
+        format!("{}", hash.sparse()[0] * hash.sparse()[1])
     }
 
     fn star2(&self, input: &str) -> String {
@@ -27,10 +28,10 @@ mod tests {
 
     #[test]
     fn star1() {
-        let mut v: Vec<_> = (0..=4).collect();
-        let lengths = vec![3, 4, 1, 5];
-        knot(&mut v, &lengths, 0, 0);
-        assert_eq!(v[0] * v[1], 12);
+        let hash = KnotHasher::new(Params::single_round(5))
+            .update_lengths(&[3, 4, 1, 5])
+            .finish();
+        assert_eq!(hash.sparse()[0] * hash.sparse()[1], 12);
     }
 
     #[test]
@@ -1,49 +1,28 @@
 use common::day::Day;
 use std::collections::VecDeque;
 use util::grid2d::{Coords, Grid2D};
-use util::knothash::KnotHash;
+use util::knothash::{bit_grid, KnotHash};
 
 pub struct Day14 {}
 
-fn convert_to_grid(hashes: &[KnotHash]) -> Grid2D<char> {
-    let mut grid = Grid2D::with_default(Coords { x: 128, y: 128 }, &'.');
-    for (y, hash) in hashes.iter().enumerate() {
-        for (i, byte) in hash.bytes().enumerate() {
-            for j in 0..8 {
-                let v = if byte & (1 << j) > 0 { '#' } else { '.' };
-                let x = i * 8 + (7 - j);
-                grid.set(
-                    &Coords {
-                        x: x as i64,
-                        y: y as i64,
-                    },
-                    v,
-                );
-            }
-        }
-    }
-    grid
+fn disk(prefix: &str) -> Grid2D<bool> {
+    let hashes: Vec<_> = (0..128)
+        .map(|i| KnotHash::from(&format!("{}-{}", prefix, i)))
+        .collect();
+    bit_grid(&hashes)
 }
 
-fn calc_hashes(prefix: &str) -> Vec<KnotHash> {
-    (0..128)
-        .map(|i| {
-            let hash_string = format!("{}-{}", prefix, i);
-            KnotHash::from(&hash_string)
-        })
-        .collect()
-}
-
-fn fill_region(grid: &mut Grid2D<char>, start: &Coords) {
+// Clears the region containing start.
+fn clear_region(grid: &mut Grid2D<bool>, start: &Coords) {
     let mut frontier = VecDeque::new();
     frontier.push_back(*start);
-    grid.set(start, 'F');
+    grid.set(start, false);
 
     while let Some(cur) = frontier.pop_front() {
         for neighbor in grid.neighbors_cardinal_coords(&cur).iter() {
-            if let Some(&'#') = grid.at(neighbor) {
+            if let Some(true) = grid.at(neighbor) {
                 frontier.push_back(*neighbor);
-                grid.set(neighbor, 'F');
+                grid.set(neighbor, false);
             }
         }
     }
@@ -51,23 +30,18 @@ fn fill_region(grid: &mut Grid2D<char>, start: &Coords) {
 
 impl Day for Day14 {
     fn star1(&self, input: &str) -> String {
-        let hashes = calc_hashes(input.trim());
-        let used_squares = hashes
-            .iter()
-            .map(|hash| hash.bytes().map(|x| x.count_ones()).sum::<u32>())
-            .sum::<u32>();
-        format!("{}", used_squares)
+        let grid = disk(input.trim());
+        format!("{}", grid.count(true))
     }
 
     fn star2(&self, input: &str) -> String {
-        let hashes = calc_hashes(input.trim());
-        let mut grid = convert_to_grid(&hashes);
+        let mut grid = disk(input.trim());
         let all_coords: Vec<_> = grid.coords_iter().collect();
         let regions = all_coords
             .iter()
             .filter(|c| {
-                if grid.at(c).unwrap() == &'#' {
-                    fill_region(&mut grid, c);
+                if grid.at(c) == Some(&true) {
+                    clear_region(&mut grid, c);
                     true
                 } else {
                     false
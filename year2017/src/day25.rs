@@ -140,6 +140,10 @@ impl Day for Day25 {
     fn star2(&self, _input: &str) -> String {
         String::from("not implemented")
     }
+
+    fn stars(&self) -> usize {
+        1
+    }
 }
 
 #[cfg(test)]
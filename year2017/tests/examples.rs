@@ -0,0 +1,8 @@
+use common::year::Year;
+use std::process::ExitCode;
+use year2017::Year2017;
+
+// Runs the fixtures in examples/year2017.
+fn main() -> ExitCode {
+    common::fixture::harness(2017, |day_no| Year2017 {}.get_day(day_no))
+}
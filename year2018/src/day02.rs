@@ -1,11 +1,11 @@
 use common::day::Day;
-use util::chardistrib::char_distribution;
+use util::counter::Counter;
 
 pub struct Day02 {}
 
 fn occurs_n_times(s: &str, n: usize) -> bool {
-    let distrib = char_distribution(s);
-    distrib.values().any(|&x| x == n)
+    let distrib: Counter<_> = s.chars().collect();
+    distrib.counts_sorted().contains(&n)
 }
 
 fn same_chars(s1: &str, s2: &str) -> String {
@@ -1,6 +1,7 @@
 use common::day::Day;
 use std::collections::BTreeMap;
 use util::grid2d::{Coords, Direction, Grid2D};
+use util::viz;
 
 pub struct Day13 {}
 
@@ -24,6 +25,9 @@ enum NextTurn {
 
 impl State {
     fn tick(&mut self, remove: bool) -> Option<Coords> {
+        if viz::is_enabled() {
+            viz::frame(&self.to_grid());
+        }
         let mut new_carts = BTreeMap::new();
         loop {
             let cart_pos = match self.carts.keys().next() {
@@ -239,9 +243,8 @@ impl State {
         }
     }
 
-    #[allow(dead_code)]
-    fn print(&self) {
-        let mut print_grid = self.grid.clone();
+    fn to_grid(&self) -> Grid2D<char> {
+        let mut grid = self.grid.clone();
         for (cart_pos, cart) in self.carts.iter() {
             let dir_char = match cart.direction {
                 Direction::N => '^',
@@ -252,9 +255,9 @@ impl State {
                     panic!("Invalid direction!");
                 }
             };
-            print_grid.set(cart_pos, dir_char);
+            grid.set(cart_pos, dir_char);
         }
-        println!("{}\n", print_grid);
+        grid
     }
 }
 
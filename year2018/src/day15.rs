@@ -1,6 +1,7 @@
 use common::day::Day;
 use std::collections::{BTreeSet, HashSet, VecDeque};
 use util::grid2d::{Coords, Grid2D};
+use util::viz;
 
 pub struct Day15 {}
 
@@ -148,6 +149,9 @@ fn find_in_range(
 fn run(grid: &mut Grid2D<Field>) -> usize {
     let mut round_counter = 0;
     loop {
+        if viz::is_enabled() {
+            viz::frame(&Grid2D::new(&grid_to_str(grid)).unwrap());
+        }
         if round(grid) {
             break;
         }
@@ -272,7 +276,6 @@ fn parse_input(input: &str, elf_power: u32) -> Grid2D<Field> {
     .unwrap()
 }
 
-#[allow(dead_code)]
 fn grid_to_str(grid: &Grid2D<Field>) -> String {
     let mut s = String::new();
     for (pos, field) in grid.enumerate() {
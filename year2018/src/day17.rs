@@ -1,6 +1,7 @@
 use common::day::Day;
 use regex::Regex;
 use util::grid2d::{Coords, Direction, Grid2D};
+use util::viz;
 
 struct ScanLine {
     axis_fix: char,
@@ -101,6 +102,8 @@ fn flow(mut grid: Grid2D<char>) -> Grid2D<char> {
     // println!("Flow starts at: {}", heads[0]);
 
     while let Some(head) = heads.pop() {
+        viz::frame(&grid);
+
         // stop if bottom reached
         if head.y >= grid.height() - 1_i64 {
             continue;
@@ -1,5 +1,6 @@
 use common::day::Day;
-use util::wrist::Wrist;
+use util::wrist::{Hook, Wrist};
+use util::wristlift::{run_accelerated, Lifted};
 
 pub struct Day19 {}
 
@@ -20,14 +21,41 @@ fn factors(n: usize) -> Vec<usize> {
 impl Day for Day19 {
     fn star1(&self, input: &str) -> String {
         let mut wrist = Wrist::new_from_str(input);
-        wrist.run();
+        let idioms = Lifted::new(&wrist).idioms;
+        run_accelerated(&mut wrist, &idioms).unwrap();
         format!("{}", wrist.reg[0])
     }
 
     fn star2(&self, input: &str) -> String {
         let mut wrist = Wrist::new_from_str(input);
         wrist.reg[0] = 1;
-        let max_reg = wrist.run_y2018_s2(30);
+
+        // the divisor sum loop can be run natively if it was recognized
+        let idioms = Lifted::new(&wrist).idioms;
+        if !idioms.is_empty() {
+            run_accelerated(&mut wrist, &idioms).unwrap();
+            return format!("{}", wrist.reg[0]);
+        }
+
+        // otherwise: the program first sets up the number to factorize (the largest
+        // register value), so only the first few cycles are needed
+        let ip_reg = wrist.ip_reg;
+        let mut cycles = 0;
+        let mut max_reg = 0;
+        wrist
+            .run_with(|_, _, reg| {
+                max_reg = (0..6)
+                    .filter(|&i| i != ip_reg)
+                    .map(|i| reg[i])
+                    .fold(max_reg, usize::max);
+                cycles += 1;
+                if cycles > 30 {
+                    Hook::Stop
+                } else {
+                    Hook::Continue
+                }
+            })
+            .unwrap();
         let sum_factors = factors(max_reg).into_iter().sum::<usize>();
         format!("{}", sum_factors)
     }
@@ -52,4 +80,39 @@ seti 9 0 5"#;
 
         assert_eq!(d.star1(input), "6");
     }
+
+    #[test]
+    fn divisor_sum() {
+        let d = Day19 {};
+
+        // the loop of the real input, summing the divisors of 6, or of 12
+        // for star 2
+        let input = r#"#ip 3
+addi 3 16 3
+seti 1 8 1
+seti 1 3 4
+mulr 1 4 2
+eqrr 2 5 2
+addr 2 3 3
+addi 3 1 3
+addr 1 0 0
+addi 4 1 4
+gtrr 4 5 2
+addr 3 2 3
+seti 2 6 3
+addi 1 1 1
+gtrr 1 5 2
+addr 2 3 3
+seti 1 2 3
+mulr 3 3 3
+seti 6 0 5
+addr 3 0 3
+seti 0 0 3
+muli 5 2 5
+seti 0 0 0
+seti 0 0 3"#;
+
+        assert_eq!(d.star1(input), "12");
+        assert_eq!(d.star2(input), "28");
+    }
 }
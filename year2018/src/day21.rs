@@ -1,16 +1,65 @@
 use common::day::Day;
-use util::wrist::Wrist;
+use std::collections::HashSet;
+use util::wrist::{Hook, Instruction, Op, Registers, Wrist};
+use util::wristlift::{accelerate, Lifted};
 
 pub struct Day21 {}
 
+// The program halts at the only eqrr instruction, when the register
+// compared with register 0 matches. Returns that register value.
+fn halt_value(instr: &Instruction, reg: &Registers) -> Option<usize> {
+    if instr.op == Op::Eqrr {
+        Some(if instr.a == 0 {
+            reg[instr.b]
+        } else {
+            reg[instr.a]
+        })
+    } else {
+        None
+    }
+}
+
 impl Day for Day21 {
     fn star1(&self, input: &str) -> String {
         let mut wrist = Wrist::new_from_str(input);
-        format!("{}", wrist.run_d21_s1().unwrap())
+        let mut first = None;
+        wrist
+            .run_with(|_, instr, reg| match halt_value(instr, reg) {
+                Some(v) => {
+                    first = Some(v);
+                    Hook::Stop
+                }
+                None => Hook::Continue,
+            })
+            .unwrap();
+        format!("{}", first.unwrap())
     }
 
     fn star2(&self, input: &str) -> String {
         let mut wrist = Wrist::new_from_str(input);
-        format!("{}", wrist.run_d21_s2().unwrap())
+        // the division loop is the bottleneck, run it natively
+        let idioms = Lifted::new(&wrist).idioms;
+        let ip_reg = wrist.ip_reg;
+        let mut values = HashSet::new();
+        let mut last_inserted = None;
+        wrist
+            .run_with(accelerate(
+                &idioms,
+                ip_reg,
+                |_, instr, reg| match halt_value(instr, reg) {
+                    Some(v) => {
+                        if values.insert(v) {
+                            last_inserted = Some(v);
+                            Hook::Continue
+                        } else {
+                            // values repeat from here on
+                            Hook::Stop
+                        }
+                    }
+                    None => Hook::Continue,
+                },
+            ))
+            .unwrap();
+        format!("{}", last_inserted.unwrap())
     }
 }
@@ -1,5 +1,6 @@
 use common::day::Day;
 use scan_fmt::scan_fmt;
+use util::cancel;
 
 pub struct Day23 {}
 
@@ -212,6 +213,7 @@ fn search(bots: &[Bot]) -> Vec<Vec3D> {
     while length > 1 {
         let mut next_cubes = vec![];
         for cube in cubes {
+            cancel::check();
             let cube_splits = cube.split();
             for c in cube_splits {
                 next_cubes.push((c, c.bots_in_range(bots)));
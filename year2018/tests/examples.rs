@@ -0,0 +1,8 @@
+use common::year::Year;
+use std::process::ExitCode;
+use year2018::Year2018;
+
+// Runs the fixtures in examples/year2018.
+fn main() -> ExitCode {
+    common::fixture::harness(2018, |day_no| Year2018 {}.get_day(day_no))
+}
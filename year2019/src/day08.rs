@@ -1,8 +1,12 @@
 use common::day::Day;
-use util::chardistrib;
+use common::params::{Param, ParamInfo, Params};
+use util::counter::Counter;
 
 pub struct Day08 {}
 
+const WIDTH: Param<usize> = Param::new("width", 25);
+const HEIGHT: Param<usize> = Param::new("height", 6);
+
 fn get_layers(input: &str, width: usize, height: usize) -> Vec<&str> {
     let layer_size = width * height;
     let mut r = vec![];
@@ -18,10 +22,10 @@ fn get_min_zero_layer(input: &str, width: usize, height: usize) -> usize {
     let layers = get_layers(input, width, height);
     let min_distrib = layers
         .iter()
-        .map(|l| chardistrib::char_distribution(l))
-        .min_by(|d1, d2| d1.get(&'0').unwrap_or(&0).cmp(d2.get(&'0').unwrap_or(&0)))
+        .map(|l| l.chars().collect::<Counter<_>>())
+        .min_by_key(|d| d[&'0'])
         .unwrap();
-    min_distrib.get(&'1').unwrap_or(&0) * min_distrib.get(&'2').unwrap_or(&0)
+    min_distrib[&'1'] * min_distrib[&'2']
 }
 
 fn decode(input: &str, width: usize, height: usize) -> String {
@@ -52,11 +56,24 @@ fn decode(input: &str, width: usize, height: usize) -> String {
 
 impl Day for Day08 {
     fn star1(&self, input: &str) -> String {
-        format!("{}", get_min_zero_layer(input.trim(), 25, 6))
+        self.star1_with(input, &Params::default())
     }
 
     fn star2(&self, input: &str) -> String {
-        decode(input, 25, 6)
+        self.star2_with(input, &Params::default())
+    }
+
+    fn star1_with(&self, input: &str, params: &Params) -> String {
+        let (width, height) = (params.get(&WIDTH), params.get(&HEIGHT));
+        format!("{}", get_min_zero_layer(input.trim(), width, height))
+    }
+
+    fn star2_with(&self, input: &str, params: &Params) -> String {
+        decode(input, params.get(&WIDTH), params.get(&HEIGHT))
+    }
+
+    fn params(&self) -> Vec<ParamInfo> {
+        vec![WIDTH.info(), HEIGHT.info()]
     }
 }
 
@@ -67,5 +84,16 @@ mod tests {
     #[test]
     fn ex1() {
         assert_eq!(get_min_zero_layer("123456789012", 3, 2), 1);
+
+        let d = Day08 {};
+        let params = Params::from([("width", "3"), ("height", "2")]);
+        assert_eq!(d.star1_with("123456789012", &params), "1");
+    }
+
+    #[test]
+    fn ex2() {
+        let d = Day08 {};
+        let params = Params::from([("width", "2"), ("height", "2")]);
+        assert_eq!(d.star2_with("0222112222120000", &params), " █\n█ ");
     }
 }
@@ -1,7 +1,9 @@
 use common::day::Day;
 use std::cmp::Ordering;
 use std::collections::HashMap;
+use util::grid2d::{Coords, Grid2D};
 use util::intcode::{IntSize, Intcode, RunState};
+use util::viz;
 
 pub struct Day13 {}
 
@@ -22,12 +24,32 @@ fn read_screen(screen: &mut Screen, intcode: &mut Intcode) -> IntSize {
     score
 }
 
+fn screen_to_grid(screen: &Screen) -> Grid2D<char> {
+    let x_max = screen.keys().map(|k| k.0).max().unwrap_or(0);
+    let y_max = screen.keys().map(|k| k.1).max().unwrap_or(0);
+    let mut grid = Grid2D::with_default(Coords::from((x_max + 1, y_max + 1)), &' ');
+    for ((x, y), id) in screen {
+        let c = match id {
+            1 => '#',
+            2 => 'x',
+            3 => '-',
+            4 => 'o',
+            _ => ' ',
+        };
+        grid.set(&Coords::from((*x, *y)), c);
+    }
+    grid
+}
+
 fn play(intcode: &mut Intcode) -> IntSize {
     let mut screen = HashMap::new();
     let mut score = 0;
 
     while intcode.state != RunState::Halted {
         score = read_screen(&mut screen, intcode);
+        if viz::is_enabled() {
+            viz::frame(&screen_to_grid(&screen));
+        }
         let paddle_x = screen
             .iter()
             .find(|(_, v)| v == &&3)
@@ -0,0 +1,8 @@
+use common::year::Year;
+use std::process::ExitCode;
+use year2019::Year2019;
+
+// Runs the fixtures in examples/year2019.
+fn main() -> ExitCode {
+    common::fixture::harness(2019, |day_no| Year2019 {}.get_day(day_no))
+}
@@ -1,35 +1,34 @@
 use common::day::Day;
+use util::combos::combinations_where;
 
 pub struct Day01 {}
 
 impl Day for Day01 {
     fn star1(&self, input: &str) -> String {
         let entries = parse_input(input);
-        for i in 0..entries.len() {
-            for j in i + 1..entries.len() {
-                if entries[i] + entries[j] == 2020 {
-                    return format!("{}", entries[i] * entries[j]);
-                }
-            }
+        match find_entries(&entries, 2, 2020) {
+            Some(product) => format!("{}", product),
+            None => String::from("not found"),
         }
-        String::from("not found")
     }
 
     fn star2(&self, input: &str) -> String {
         let entries = parse_input(input);
-        for i in 0..entries.len() {
-            for j in i + 1..entries.len() {
-                for k in j + 1..entries.len() {
-                    if entries[i] + entries[j] + entries[k] == 2020 {
-                        return format!("{}", entries[i] * entries[j] * entries[k]);
-                    }
-                }
-            }
+        match find_entries(&entries, 3, 2020) {
+            Some(product) => format!("{}", product),
+            None => String::from("not found"),
         }
-        String::from("not found")
     }
 }
 
+// product of the k entries summing up to target
+fn find_entries(entries: &[u64], k: usize, target: u64) -> Option<u64> {
+    let sum = |c: &[usize]| c.iter().map(|i| entries[*i]).sum::<u64>();
+    combinations_where(entries.len(), k, |c| sum(c) <= target)
+        .find(|c| sum(c) == target)
+        .map(|c| c.iter().map(|i| entries[*i]).product())
+}
+
 fn parse_input(input: &str) -> Vec<u64> {
     input
         .lines()
@@ -1,154 +1,29 @@
 use common::day::Day;
+use util::expr::{Arith, Expr, InfixParser};
 
 pub struct Day18 {}
 
-#[derive(PartialEq)]
-enum Token {
-    Num(i64),
-    Op(Operator),
-    ParenLeft,
-    ParenRight,
-}
-
-#[derive(PartialEq)]
-enum Operator {
-    Plus,
-    Mul,
-}
-
-fn precedence_part1(op: &Operator) -> usize {
-    match op {
-        Operator::Plus => 1,
-        Operator::Mul => 1,
-    }
-}
-
-fn precedence_part2(op: &Operator) -> usize {
-    match op {
-        Operator::Plus => 2,
-        Operator::Mul => 1,
-    }
-}
-
-fn parse_token(input: &str) -> Option<Token> {
-    if input.parse::<i64>().is_ok() {
-        Some(Token::Num(input.parse::<i64>().unwrap()))
-    } else if input.len() > 1 || input.is_empty() {
-        None
-    } else {
-        match input.chars().next().unwrap() {
-            '+' => Some(Token::Op(Operator::Plus)),
-            '*' => Some(Token::Op(Operator::Mul)),
-            '(' => Some(Token::ParenLeft),
-            ')' => Some(Token::ParenRight),
-            _ => None,
-        }
-    }
-}
-
-fn convert_rpn<F>(input: &[&str], precedence_fn: &F) -> Vec<Token>
-where
-    F: Fn(&Operator) -> usize,
-{
-    let mut op_stack = vec![];
-    let mut output = vec![];
-
-    for token in input {
-        let token = parse_token(token).unwrap();
-        match &token {
-            Token::Num(_) => {
-                output.push(token);
-            }
-            Token::Op(op) => {
-                while !op_stack.is_empty() {
-                    let top = op_stack.last().unwrap();
-                    match top {
-                        Token::ParenLeft => {
-                            break;
-                        }
-                        Token::Op(top_op) => {
-                            if precedence_fn(top_op) < precedence_fn(op) {
-                                break;
-                            }
-                        }
-                        _ => {}
-                    }
-                    output.push(op_stack.pop().unwrap());
-                }
-                op_stack.push(token);
-            }
-            Token::ParenLeft => {
-                op_stack.push(token);
-            }
-            Token::ParenRight => {
-                while op_stack.last().unwrap() != &Token::ParenLeft {
-                    output.push(op_stack.pop().unwrap());
-                }
-                op_stack.pop().unwrap();
-            }
-        }
-    }
+const PRECEDENCE_PART1: [(&str, Arith, usize); 2] = [("+", Arith::Add, 1), ("*", Arith::Mul, 1)];
+const PRECEDENCE_PART2: [(&str, Arith, usize); 2] = [("+", Arith::Add, 2), ("*", Arith::Mul, 1)];
 
-    while let Some(el) = op_stack.pop() {
-        output.push(el);
-    }
-
-    output
-}
-
-fn eval_rpn(expr: &[Token]) -> i64 {
-    let mut num_stack = vec![];
-    for token in expr {
-        match &token {
-            Token::Num(n) => {
-                num_stack.push(*n);
-            }
-            Token::Op(op) => {
-                let p1 = num_stack.pop().unwrap();
-                let p2 = num_stack.pop().unwrap();
-                let res = match op {
-                    Operator::Plus => p1 + p2,
-                    Operator::Mul => p1 * p2,
-                };
-                num_stack.push(res);
-            }
-            Token::ParenLeft => {
-                panic!("( in eval!");
-            }
-            Token::ParenRight => {
-                panic!("( in eval!");
-            }
-        }
-    }
-    num_stack.pop().unwrap()
+fn parse_input(input: &str, precedence: &[(&str, Arith, usize)]) -> Vec<Expr<i64, Arith>> {
+    let parser = InfixParser::new(precedence);
+    input.lines().map(|l| parser.parse(l).unwrap()).collect()
 }
 
-fn parse_input<F>(input: &str, precedence_fn: &F) -> Vec<Vec<Token>>
-where
-    F: Fn(&Operator) -> usize,
-{
-    input
-        .lines()
-        .map(|l| {
-            let l = l.replace('(', " ( ");
-            let l = l.replace(')', " ) ");
-            let tokens: Vec<_> = l.split_whitespace().collect();
-            convert_rpn(&tokens, precedence_fn)
-        })
-        .collect()
+fn sum(exprs: &[Expr<i64, Arith>]) -> i64 {
+    exprs.iter().map(|e| e.eval(&|_| None).unwrap()).sum()
 }
 
 impl Day for Day18 {
     fn star1(&self, input: &str) -> String {
-        let exprs = parse_input(input, &precedence_part1);
-        let sum: i64 = exprs.iter().map(|e| eval_rpn(e)).sum();
-        format!("{}", sum)
+        let exprs = parse_input(input, &PRECEDENCE_PART1);
+        format!("{}", sum(&exprs))
     }
 
     fn star2(&self, input: &str) -> String {
-        let exprs = parse_input(input, &precedence_part2);
-        let sum: i64 = exprs.iter().map(|e| eval_rpn(e)).sum();
-        format!("{}", sum)
+        let exprs = parse_input(input, &PRECEDENCE_PART2);
+        format!("{}", sum(&exprs))
     }
 }
 
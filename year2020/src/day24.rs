@@ -2,24 +2,6 @@ use common::day::Day;
 use std::collections::HashSet;
 use util::hex::HexCoord;
 
-pub const NEIGHBORS_PLUS_SELF: [HexCoord; 7] = [
-    HexCoord(0, 0),
-    HexCoord(1, 0),
-    HexCoord(0, 1),
-    HexCoord(-1, 1),
-    HexCoord(-1, 0),
-    HexCoord(0, -1),
-    HexCoord(1, -1),
-];
-pub const NEIGHBORS: [HexCoord; 6] = [
-    HexCoord(1, 0),
-    HexCoord(0, 1),
-    HexCoord(-1, 1),
-    HexCoord(-1, 0),
-    HexCoord(0, -1),
-    HexCoord(1, -1),
-];
-
 pub struct Day24 {}
 
 fn parse_input(input: &str) -> Vec<Vec<HexCoord>> {
@@ -59,11 +41,12 @@ fn evolve(tiles: HashSet<HexCoord>) -> HashSet<HexCoord> {
     let mut new_tiles = HashSet::new();
     let mut checked_tiles = HashSet::new();
     for tile in tiles.iter() {
-        for active in NEIGHBORS_PLUS_SELF.iter().map(|n| *tile + *n) {
+        for active in tile.spiral(1) {
             if checked_tiles.insert(active) {
-                let num_black_neighbors = NEIGHBORS
+                let num_black_neighbors = active
+                    .neighbors()
                     .iter()
-                    .filter(|n| tiles.contains(&(active + **n)))
+                    .filter(|n| tiles.contains(n))
                     .count();
                 if num_black_neighbors == 2 || (num_black_neighbors == 1 && tiles.contains(&active))
                 {
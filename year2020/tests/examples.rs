@@ -0,0 +1,8 @@
+use common::year::Year;
+use std::process::ExitCode;
+use year2020::Year2020;
+
+// Runs the fixtures in examples/year2020.
+fn main() -> ExitCode {
+    common::fixture::harness(2020, |day_no| Year2020 {}.get_day(day_no))
+}
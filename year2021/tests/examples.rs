@@ -0,0 +1,8 @@
+use common::year::Year;
+use std::process::ExitCode;
+use year2021::Year2021;
+
+// Runs the fixtures in examples/year2021.
+fn main() -> ExitCode {
+    common::fixture::harness(2021, |day_no| Year2021 {}.get_day(day_no))
+}
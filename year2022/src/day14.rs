@@ -1,6 +1,7 @@
 use common::day::Day;
 
 use util::grid2d::{Coords, Grid2D};
+use util::viz;
 
 struct Map {
     source: Coords,
@@ -127,7 +128,9 @@ impl Map {
 }
 
 fn run(mut map: Map) -> usize {
-    while map.pour() {}
+    while map.pour() {
+        viz::frame(&map.grid);
+    }
     map.grid.count('o')
 }
 
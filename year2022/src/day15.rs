@@ -1,9 +1,12 @@
 use common::day::Day;
+use common::params::{Param, ParamInfo, Params};
 use std::collections::HashSet;
 use util::grid2d::Coords;
 
 pub struct Day15 {}
 
+const ROW: Param<i64> = Param::new("row", 2000000);
+
 struct Sensor {
     pos: Coords,
     beacon: Coords,
@@ -25,7 +28,7 @@ impl Sensor {
 struct Interval(i64, i64);
 
 fn merge(mut input: Vec<Interval>) -> Vec<Interval> {
-    input.sort_unstable_by(|a, b| b.0.cmp(&a.0)); // sort by max first
+    input.sort_unstable_by_key(|a| std::cmp::Reverse(a.0)); // sort by max first
     let mut output = vec![input.pop().unwrap()];
     while !input.is_empty() && output.last().unwrap().1 != i64::MAX {
         let next = input.pop().unwrap();
@@ -113,8 +116,17 @@ fn find_beacon(sensors: &[Sensor], y_min: i64, y_max: i64) -> Option<Coords> {
 
 impl Day for Day15 {
     fn star1(&self, input: &str) -> String {
+        self.star1_with(input, &Params::default())
+    }
+
+    fn params(&self) -> Vec<ParamInfo> {
+        vec![ROW.info()]
+    }
+
+    fn star1_with(&self, input: &str, params: &Params) -> String {
         let sensors: Vec<_> = parse_input(input).collect();
-        format!("{}", beacon_free_fields_in_row(&sensors, 2000000))
+        let row = params.get(&ROW);
+        format!("{}", beacon_free_fields_in_row(&sensors, row))
     }
 
     fn star2(&self, input: &str) -> String {
@@ -161,28 +173,4 @@ mod tests {
 
         assert_eq!(sensor.coverage_row(10), Some(Interval(2, 14)));
     }
-
-    #[test]
-    fn ex1() {
-        let input = r#"Sensor at x=2, y=18: closest beacon is at x=-2, y=15
-Sensor at x=9, y=16: closest beacon is at x=10, y=16
-Sensor at x=13, y=2: closest beacon is at x=15, y=3
-Sensor at x=12, y=14: closest beacon is at x=10, y=16
-Sensor at x=10, y=20: closest beacon is at x=10, y=16
-Sensor at x=14, y=17: closest beacon is at x=10, y=16
-Sensor at x=8, y=7: closest beacon is at x=2, y=10
-Sensor at x=2, y=0: closest beacon is at x=2, y=10
-Sensor at x=0, y=11: closest beacon is at x=2, y=10
-Sensor at x=20, y=14: closest beacon is at x=25, y=17
-Sensor at x=17, y=20: closest beacon is at x=21, y=22
-Sensor at x=16, y=7: closest beacon is at x=15, y=3
-Sensor at x=14, y=3: closest beacon is at x=15, y=3
-Sensor at x=20, y=1: closest beacon is at x=15, y=3"#;
-
-        let sensors: Vec<_> = parse_input(input).collect();
-        // part 1
-        assert_eq!(beacon_free_fields_in_row(&sensors, 10), 26);
-        // part 2
-        assert_eq!(find_beacon(&sensors, 0, 20), Some(Coords { x: 14, y: 11 }));
-    }
 }
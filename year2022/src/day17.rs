@@ -1,11 +1,15 @@
 use common::day::Day;
 use std::collections::HashMap;
+use util::grid2d::Grid2D;
+use util::viz;
 
 pub struct Day17 {}
 
 const ROW_WIDTH: usize = 7;
 type Row = [bool; ROW_WIDTH];
 const CYCLE_HEIGHT: usize = 30;
+// rows shown when visualized
+const VIZ_HEIGHT: usize = 40;
 
 struct Rock {
     shape: Shape,
@@ -240,19 +244,18 @@ fn empty_on_top(stack: &[Row]) -> usize {
     }
 }
 
-#[allow(dead_code)]
-fn print_stack(stack: &[Row]) {
-    for row in stack.iter().rev() {
-        let s: String = row
-            .iter()
+// The top rows of the stack, padded to the same height for all frames.
+fn stack_to_grid(stack: &[Row], height: usize) -> Grid2D<char> {
+    let mut rows = vec![".".repeat(ROW_WIDTH); height.saturating_sub(stack.len())];
+    rows.extend(stack.iter().rev().take(height).map(|row| {
+        row.iter()
             .map(|field| match field {
                 true => '#',
                 false => '.',
             })
-            .collect();
-        println!("{}", s);
-    }
-    println!();
+            .collect::<String>()
+    }));
+    Grid2D::new(&rows.join("\n")).unwrap()
 }
 
 fn simulate(jet_pattern: &[char], target_rocks: usize, check_cycle: bool) -> usize {
@@ -281,7 +284,9 @@ fn simulate(jet_pattern: &[char], target_rocks: usize, check_cycle: bool) -> usi
             if !rock.move_down(&stack) {
                 rock.settle(&mut stack);
                 rocks_fallen += 1;
-                // print_stack(&stack);
+                if viz::is_enabled() {
+                    viz::frame(&stack_to_grid(&stack, VIZ_HEIGHT));
+                }
                 break;
             }
         }
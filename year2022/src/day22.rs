@@ -1,256 +1,95 @@
 use common::day::Day;
+use util::cubenet::CubeNet;
 use util::grid2d::{Coords, Direction, Grid2D};
 
 pub struct Day22 {}
 
-type SideMap = [[(usize, Face, bool); 4]; 6];
-
-const TILES_PT1: SideMap = [
-    [
-        (1, Face::E, false),
-        (2, Face::S, false),
-        (1, Face::W, false),
-        (4, Face::N, false),
-    ],
-    [
-        (0, Face::E, false),
-        (1, Face::S, false),
-        (0, Face::W, false),
-        (1, Face::N, false),
-    ],
-    [
-        (2, Face::E, false),
-        (4, Face::S, false),
-        (2, Face::W, false),
-        (0, Face::N, false),
-    ],
-    [
-        (4, Face::E, false),
-        (5, Face::S, false),
-        (4, Face::W, false),
-        (5, Face::N, false),
-    ],
-    [
-        (3, Face::E, false),
-        (0, Face::S, false),
-        (3, Face::W, false),
-        (2, Face::N, false),
-    ],
-    [
-        (5, Face::E, false),
-        (3, Face::S, false),
-        (5, Face::W, false),
-        (3, Face::N, false),
-    ],
-];
-
-const TILES_PT2: SideMap = [
-    [
-        (1, Face::E, false),
-        (2, Face::S, false),
-        (3, Face::E, true),
-        (5, Face::E, false),
-    ],
-    [
-        (4, Face::W, true),
-        (2, Face::W, false),
-        (0, Face::W, false),
-        (5, Face::N, false),
-    ],
-    [
-        (1, Face::N, false),
-        (4, Face::S, false),
-        (3, Face::S, false),
-        (0, Face::N, false),
-    ],
-    [
-        (4, Face::E, false),
-        (5, Face::S, false),
-        (0, Face::E, true),
-        (2, Face::E, false),
-    ],
-    [
-        (1, Face::W, true),
-        (5, Face::W, false),
-        (3, Face::W, false),
-        (2, Face::N, false),
-    ],
-    [
-        (4, Face::N, false),
-        (1, Face::S, false),
-        (0, Face::S, false),
-        (3, Face::N, false),
-    ],
-];
-
-struct Side {
-    top_left: Coords,
-    tiles: Grid2D<char>,
-}
-
-fn parse_sides(input: &str, length: i64) -> Vec<Side> {
-    let lines: Vec<_> = input.lines().collect();
-
-    let mut sides = vec![];
-    for (row_idx, row) in lines.chunks(length as usize).enumerate() {
-        let row = row.join("\n");
-        let g = Grid2D::new(&row).unwrap();
-        let cols = g.width() / length;
-
-        for col in 0..cols {
-            let top_left = Coords {
-                x: col * length,
-                y: 0,
-            };
-            let bottom_right = Coords {
-                x: (col + 1) * length,
-                y: length,
-            };
-            let tiles = g.clip(top_left, bottom_right).unwrap();
-            if tiles.at(&Coords { x: 0, y: 0 }).unwrap() != &' ' {
-                sides.push(Side {
-                    top_left: top_left
-                        + Coords {
-                            x: 0,
-                            y: row_idx as i64 * length,
-                        },
-                    tiles,
-                });
-            }
-        }
-    }
-    sides
+enum Edges {
+    Flat,
+    Cube(CubeNet),
 }
 
-#[derive(Debug)]
 struct Position {
-    side: usize,
     coords: Coords,
-    face: Face,
-}
-
-#[repr(usize)]
-#[derive(Copy, Clone, Debug)]
-enum Face {
-    E = 0,
-    S = 1,
-    W = 2,
-    N = 3,
+    face: Direction,
 }
 
 impl Position {
-    fn init() -> Position {
+    fn init(map: &Grid2D<char>) -> Position {
         Position {
-            side: 0,
-            coords: Coords { x: 0, y: 0 },
-            face: Face::E,
+            coords: map.find('.').unwrap(),
+            face: Direction::E,
         }
     }
 
-    fn password(&self, sides: &[Side]) -> usize {
-        let coords_global = sides[self.side].top_left + self.coords;
-        (coords_global.y + 1) as usize * 1000
-            + (coords_global.x + 1) as usize * 4
-            + self.face as usize
+    fn password(&self) -> usize {
+        let face_value = match self.face {
+            Direction::E => 0,
+            Direction::S => 1,
+            Direction::W => 2,
+            Direction::N => 3,
+            _ => unreachable!(),
+        };
+        (self.coords.y + 1) as usize * 1000 + (self.coords.x + 1) as usize * 4 + face_value
     }
 
-    fn do_instruction(&mut self, sides: &[Side], side_map: &SideMap, instr: &Instruction) {
+    fn do_instruction(&mut self, map: &Grid2D<char>, edges: &Edges, instr: &Instruction) {
         match instr {
             Instruction::Move(steps) => {
-                self.do_move(sides, side_map, *steps);
+                self.do_move(map, edges, *steps);
             }
             Instruction::TurnLeft => {
-                self.face = match self.face {
-                    Face::E => Face::N,
-                    Face::S => Face::E,
-                    Face::W => Face::S,
-                    Face::N => Face::W,
-                };
+                self.face = self.face.left90();
             }
             Instruction::TurnRight => {
-                self.face = match self.face {
-                    Face::E => Face::S,
-                    Face::S => Face::W,
-                    Face::W => Face::N,
-                    Face::N => Face::E,
-                };
+                self.face = self.face.right90();
             }
         }
     }
 
-    fn do_move(&mut self, sides: &[Side], side_map: &SideMap, steps: usize) {
-        let tile_length = sides[0].tiles.width();
+    fn do_move(&mut self, map: &Grid2D<char>, edges: &Edges, steps: usize) {
         for _ in 0..steps {
-            let mut next_coords = self.coords.mov(match self.face {
-                Face::E => Direction::E,
-                Face::S => Direction::S,
-                Face::W => Direction::W,
-                Face::N => Direction::N,
-            });
-
-            let over_edge = match self.face {
-                Face::E => next_coords.x >= tile_length,
-                Face::S => next_coords.y >= tile_length,
-                Face::W => next_coords.x < 0,
-                Face::N => next_coords.y < 0,
+            let (next_coords, next_face) = match edges {
+                Edges::Flat => (flat_step(map, &self.coords, self.face), self.face),
+                Edges::Cube(net) => net.mov(&self.coords, self.face),
             };
-
-            if over_edge {
-                let coord_in_bound = match self.face {
-                    Face::E | Face::W => self.coords.y,
-                    Face::N | Face::S => self.coords.x,
-                };
-                let (side_next, face_next, flip) = side_map[self.side][self.face as usize];
-                next_coords = match (face_next, flip) {
-                    (Face::E, true) => Coords {
-                        x: 0,
-                        y: tile_length - 1 - coord_in_bound,
-                    },
-                    (Face::E, false) => Coords {
-                        x: 0,
-                        y: coord_in_bound,
-                    },
-                    (Face::S, true) => Coords {
-                        x: tile_length - 1 - coord_in_bound,
-                        y: 0,
-                    },
-                    (Face::S, false) => Coords {
-                        x: coord_in_bound,
-                        y: 0,
-                    },
-                    (Face::W, true) => Coords {
-                        x: tile_length - 1,
-                        y: tile_length - 1 - coord_in_bound,
-                    },
-                    (Face::W, false) => Coords {
-                        x: tile_length - 1,
-                        y: coord_in_bound,
-                    },
-                    (Face::N, true) => Coords {
-                        x: tile_length - 1 - coord_in_bound,
-                        y: tile_length - 1,
-                    },
-                    (Face::N, false) => Coords {
-                        x: coord_in_bound,
-                        y: tile_length - 1,
-                    },
-                };
-                if sides[side_next].tiles.at(&next_coords).unwrap() == &'#' {
-                    break;
-                } else {
-                    self.side = side_next;
-                    self.coords = next_coords;
-                    self.face = face_next;
-                }
-            } else if sides[self.side].tiles.at(&next_coords).unwrap() == &'#' {
+            if map.at(&next_coords).unwrap() == &'#' {
                 break;
-            } else {
-                self.coords = next_coords;
             }
+            self.coords = next_coords;
+            self.face = next_face;
         }
     }
 }
 
+fn on_map(map: &Grid2D<char>, c: &Coords) -> bool {
+    map.at(c).is_some_and(|t| t != &' ')
+}
+
+fn flat_step(map: &Grid2D<char>, c: &Coords, d: Direction) -> Coords {
+    let next = c.mov(d);
+    if on_map(map, &next) {
+        next
+    } else {
+        // wrap around to the opposite end of the row / column
+        let back = d.opposite();
+        let mut cur = *c;
+        while on_map(map, &cur.mov(back)) {
+            cur = cur.mov(back);
+        }
+        cur
+    }
+}
+
+fn parse_map(input: &str) -> Grid2D<char> {
+    let width = input.lines().map(|l| l.len()).max().unwrap();
+    let padded: Vec<_> = input
+        .lines()
+        .map(|l| format!("{:width$}", l, width = width))
+        .collect();
+    Grid2D::new(&padded.join("\n")).unwrap()
+}
+
 #[derive(Debug, PartialEq)]
 enum Instruction {
     Move(usize),
@@ -282,29 +121,32 @@ fn parse_instructions(line: &str) -> Vec<Instruction> {
     instructions
 }
 
-fn run(input: &str, length: i64, side_map: &SideMap) -> usize {
+fn run(input: &str, cube: bool) -> usize {
     let mut input_it = input.split("\n\n");
-    let input_map = input_it.next().unwrap();
-    let input_instructions = input_it.next().unwrap();
+    let map = parse_map(input_it.next().unwrap());
+    let instrs = parse_instructions(input_it.next().unwrap());
 
-    let sides = parse_sides(input_map, length);
-    let instrs = parse_instructions(input_instructions);
+    let edges = if cube {
+        Edges::Cube(CubeNet::new(&map, &' ').unwrap())
+    } else {
+        Edges::Flat
+    };
 
-    let mut pos = Position::init();
+    let mut pos = Position::init(&map);
     for instr in instrs.iter() {
-        pos.do_instruction(&sides, side_map, instr);
+        pos.do_instruction(&map, &edges, instr);
     }
 
-    pos.password(&sides)
+    pos.password()
 }
 
 impl Day for Day22 {
     fn star1(&self, input: &str) -> String {
-        format!("{}", run(input, 50, &TILES_PT1))
+        format!("{}", run(input, false))
     }
 
     fn star2(&self, input: &str) -> String {
-        format!("{}", run(input, 50, &TILES_PT2))
+        format!("{}", run(input, true))
     }
 }
 
@@ -312,84 +154,6 @@ impl Day for Day22 {
 mod tests {
     use super::*;
 
-    const TILES_PT1_TEST: SideMap = [
-        [
-            (0, Face::E, false),
-            (3, Face::S, false),
-            (0, Face::W, false),
-            (4, Face::N, false),
-        ],
-        [
-            (2, Face::E, false),
-            (1, Face::S, false),
-            (3, Face::W, false),
-            (1, Face::N, false),
-        ],
-        [
-            (3, Face::E, false),
-            (2, Face::S, false),
-            (1, Face::W, false),
-            (2, Face::N, false),
-        ],
-        [
-            (1, Face::E, false),
-            (4, Face::S, false),
-            (2, Face::W, false),
-            (0, Face::N, false),
-        ],
-        [
-            (5, Face::E, false),
-            (0, Face::S, false),
-            (5, Face::W, false),
-            (3, Face::N, false),
-        ],
-        [
-            (4, Face::E, false),
-            (5, Face::S, false),
-            (4, Face::W, false),
-            (5, Face::N, false),
-        ],
-    ];
-
-    const TILES_PT2_TEST: SideMap = [
-        [
-            (5, Face::W, true),
-            (3, Face::S, false),
-            (2, Face::S, false),
-            (1, Face::S, true),
-        ],
-        [
-            (2, Face::E, false),
-            (4, Face::N, true),
-            (5, Face::N, true),
-            (0, Face::S, true),
-        ],
-        [
-            (3, Face::E, false),
-            (4, Face::E, true),
-            (1, Face::W, false),
-            (0, Face::E, false),
-        ],
-        [
-            (5, Face::S, true),
-            (4, Face::S, false),
-            (2, Face::W, false),
-            (0, Face::N, false),
-        ],
-        [
-            (5, Face::E, false),
-            (1, Face::N, true),
-            (2, Face::N, true),
-            (3, Face::N, false),
-        ],
-        [
-            (0, Face::W, true),
-            (1, Face::E, true),
-            (4, Face::W, false),
-            (3, Face::W, true),
-        ],
-    ];
-
     #[test]
     fn ex1() {
         let input = r#"        ...#
@@ -407,7 +171,8 @@ mod tests {
 
 10R5L5R10L4R5L5"#;
 
-        assert_eq!(run(input, 4, &TILES_PT1_TEST), 6032);
-        assert_eq!(run(input, 4, &TILES_PT2_TEST), 5031);
+        let d = Day22 {};
+        assert_eq!(d.star1(input), "6032");
+        assert_eq!(d.star2(input), "5031");
     }
 }
@@ -0,0 +1,8 @@
+use common::year::Year;
+use std::process::ExitCode;
+use year2022::Year2022;
+
+// Runs the fixtures in examples/year2022.
+fn main() -> ExitCode {
+    common::fixture::harness(2022, |day_no| Year2022 {}.get_day(day_no))
+}
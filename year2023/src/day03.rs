@@ -57,7 +57,7 @@ fn part_numbers_and_gears(grid: Grid2D<char>) -> (Vec<u32>, HashMap<Coords, Vec<
                 NumberParseState::None => {
                     if val.is_ascii_digit() {
                         part_number += val.to_digit(10).unwrap();
-                        gear_neighbor_set.extend(gear_neighbors(&grid, &coords).into_iter());
+                        gear_neighbor_set.extend(gear_neighbors(&grid, &coords));
 
                         if has_symbol_neighbor(&grid, &coords) {
                             state = NumberParseState::PartNumber;
@@ -70,7 +70,7 @@ fn part_numbers_and_gears(grid: Grid2D<char>) -> (Vec<u32>, HashMap<Coords, Vec<
                     if val.is_ascii_digit() {
                         part_number *= 10;
                         part_number += val.to_digit(10).unwrap();
-                        gear_neighbor_set.extend(gear_neighbors(&grid, &coords).into_iter());
+                        gear_neighbor_set.extend(gear_neighbors(&grid, &coords));
                     } else {
                         part_numbers.push(part_number);
                         for gear in &gear_neighbor_set {
@@ -86,7 +86,7 @@ fn part_numbers_and_gears(grid: Grid2D<char>) -> (Vec<u32>, HashMap<Coords, Vec<
                     if val.is_ascii_digit() {
                         part_number *= 10;
                         part_number += val.to_digit(10).unwrap();
-                        gear_neighbor_set.extend(gear_neighbors(&grid, &coords).into_iter());
+                        gear_neighbor_set.extend(gear_neighbors(&grid, &coords));
                         if has_symbol_neighbor(&grid, &coords) {
                             state = NumberParseState::PartNumber;
                         }
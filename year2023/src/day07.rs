@@ -1,6 +1,7 @@
-use std::{cmp::Ordering, collections::HashMap};
+use std::cmp::Ordering;
 
 use common::day::Day;
+use util::counter::Counter;
 
 pub struct Day07 {}
 
@@ -99,26 +100,15 @@ enum HandType {
 
 impl HandType {
     fn from(cards: &[Card; 5]) -> HandType {
-        let mut freq = HashMap::new();
-        for c in cards {
-            let e = freq.entry(c).or_insert(0);
-            *e += 1;
-        }
-
-        if freq.values().any(|v| *v == 5) {
-            HandType::Five
-        } else if freq.values().any(|v| *v == 4) {
-            HandType::Four
-        } else if freq.values().any(|v| *v == 3) && freq.values().any(|v| *v == 2) {
-            HandType::FullHouse
-        } else if freq.values().any(|v| *v == 3) {
-            HandType::Three
-        } else if freq.values().filter(|v| **v == 2).count() == 2 {
-            HandType::TwoPair
-        } else if freq.values().any(|v| *v == 2) {
-            HandType::OnePair
-        } else {
-            HandType::High
+        let freq: Counter<_> = cards.iter().collect();
+        match freq.counts_sorted()[..] {
+            [5] => HandType::Five,
+            [4, ..] => HandType::Four,
+            [3, 2] => HandType::FullHouse,
+            [3, ..] => HandType::Three,
+            [2, 2, ..] => HandType::TwoPair,
+            [2, ..] => HandType::OnePair,
+            _ => HandType::High,
         }
     }
 }
@@ -155,7 +155,7 @@ struct Coords3D {
 fn parse_input(input: &str) -> Bricks {
     let mut bricks: Vec<_> = input.lines().map(Brick::parse).collect();
     // sort by min z
-    bricks.sort_unstable_by(|a, b| a.from.z.min(a.to.z).cmp(&b.from.z.min(b.to.z)));
+    bricks.sort_unstable_by_key(|a| a.from.z.min(a.to.z));
     bricks
 }
 
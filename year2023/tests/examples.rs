@@ -0,0 +1,8 @@
+use common::year::Year;
+use std::process::ExitCode;
+use year2023::Year2023;
+
+// Runs the fixtures in examples/year2023.
+fn main() -> ExitCode {
+    common::fixture::harness(2023, |day_no| Year2023 {}.get_day(day_no))
+}
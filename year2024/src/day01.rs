@@ -1,12 +1,21 @@
-use std::collections::HashMap;
-
-use common::day::Day;
+use common::day::ParsedDay;
+use util::counter::Counter;
 
 pub struct Day01 {}
 
-impl Day for Day01 {
-    fn star1(&self, input: &str) -> String {
-        let (mut left, mut right) = parse_input(input);
+type Lists = (Vec<isize>, Vec<isize>);
+
+impl ParsedDay for Day01 {
+    type Parsed = Lists;
+    type Error = String;
+
+    fn parse(&self, input: &str) -> Result<Lists, String> {
+        parse_input(input)
+    }
+
+    fn part1(&self, (left, right): &Lists) -> String {
+        let mut left = left.clone();
+        let mut right = right.clone();
         left.sort_unstable();
         right.sort_unstable();
         format!(
@@ -18,57 +27,30 @@ impl Day for Day01 {
         )
     }
 
-    fn star2(&self, input: &str) -> String {
-        let (left, right) = parse_input(input);
-        let mut occurrence = HashMap::new();
-        for n in right {
-            let e = occurrence.entry(n).or_insert(0);
-            *e += 1;
-        }
+    fn part2(&self, (left, right): &Lists) -> String {
+        let occurrence: Counter<_> = right.iter().collect();
         let sum = left
-            .into_iter()
-            .map(|n| {
-                let o = occurrence.get(&n).unwrap_or(&0);
-                n * o
-            })
+            .iter()
+            .map(|n| n * occurrence[&n] as isize)
             .sum::<isize>();
         format!("{}", sum)
     }
 }
 
-fn parse_input(input: &str) -> (Vec<isize>, Vec<isize>) {
+fn parse_input(input: &str) -> Result<Lists, String> {
     input
         .lines()
-        .map(|line| {
-            let mut it = line.split_whitespace();
-            (
-                it.next().unwrap().parse::<isize>().unwrap(),
-                it.next().unwrap().parse::<isize>().unwrap(),
-            )
+        .enumerate()
+        .map(|(i, line)| {
+            let numbers: Vec<_> = line
+                .split_whitespace()
+                .map(|n| n.parse::<isize>())
+                .collect::<Result<_, _>>()
+                .map_err(|e| format!("line {}: {}", i + 1, e))?;
+            match numbers[..] {
+                [left, right] => Ok((left, right)),
+                _ => Err(format!("line {}: expected two numbers", i + 1)),
+            }
         })
-        .unzip()
-}
-
-#[cfg(test)]
-mod tests {
-    use super::*;
-
-    const INPUT: &str = r#"3   4
-4   3
-2   5
-1   3
-3   9
-3   3"#;
-
-    #[test]
-    fn star1() {
-        let d = Day01 {};
-        assert_eq!(d.star1(INPUT), "11");
-    }
-
-    #[test]
-    fn star2() {
-        let d = Day01 {};
-        assert_eq!(d.star2(INPUT), "31");
-    }
+        .collect()
 }
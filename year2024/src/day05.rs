@@ -1,12 +1,21 @@
 use std::collections::HashMap;
+use std::convert::Infallible;
 
-use common::day::Day;
+use common::day::ParsedDay;
 
 pub struct Day05 {}
 
-impl Day for Day05 {
-    fn star1(&self, input: &str) -> String {
-        let (page_orderings, updates) = parse_input(input);
+type Input = (Vec<PageOrdering>, Vec<Update>);
+
+impl ParsedDay for Day05 {
+    type Parsed = Input;
+    type Error = Infallible;
+
+    fn parse(&self, input: &str) -> Result<Input, Infallible> {
+        Ok(parse_input(input))
+    }
+
+    fn part1(&self, (page_orderings, updates): &Input) -> String {
         let sum = updates
             .iter()
             .filter(|update| page_orderings.iter().all(|ordering| ordering.is_ok(update)))
@@ -15,15 +24,14 @@ impl Day for Day05 {
         format!("{}", sum)
     }
 
-    fn star2(&self, input: &str) -> String {
-        let (page_orderings, updates) = parse_input(input);
+    fn part2(&self, (page_orderings, updates): &Input) -> String {
         let mut sum = 0;
 
         for update in updates {
             let mut page_numbers_old = update.page_numbers.clone();
             let page_numbers_sorted = loop {
                 let mut page_numbers_new = page_numbers_old.clone();
-                for o in &page_orderings {
+                for o in page_orderings {
                     if let (Some(idx_before), Some(idx_after)) = (
                         page_numbers_new.iter().position(|n| *n == o.before),
                         page_numbers_new.iter().position(|n| *n == o.after),
@@ -46,7 +54,7 @@ impl Day for Day05 {
     }
 }
 
-struct PageOrdering {
+pub struct PageOrdering {
     before: usize,
     after: usize,
 }
@@ -64,13 +72,13 @@ impl PageOrdering {
     }
 }
 
-struct Update {
+pub struct Update {
     pub page_numbers: Vec<usize>,
     pub positions: HashMap<usize, usize>,
     pub middle: usize,
 }
 
-fn parse_input(input: &str) -> (Vec<PageOrdering>, Vec<Update>) {
+fn parse_input(input: &str) -> Input {
     let mut secs = input.split("\n\n");
 
     let page_orderings = secs
@@ -114,6 +122,7 @@ fn parse_input(input: &str) -> (Vec<PageOrdering>, Vec<Update>) {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use common::day::Day;
 
     const INPUT: &str = r#"47|53
 97|13
@@ -1,25 +1,47 @@
 use std::collections::HashSet;
 
 use common::day::Day;
+use common::params::{Param, ParamInfo, Params};
 use regex::Regex;
 use util::grid2d::{Coords, Grid2D};
 
 pub struct Day14 {}
 
+const WIDTH: Param<i64> = Param::new("width", 101);
+const HEIGHT: Param<i64> = Param::new("height", 103);
+
 impl Day for Day14 {
     fn star1(&self, input: &str) -> String {
-        let robots = parse_input(input);
-        let limits = Coords { x: 101, y: 103 };
-        safety_factor(&robots, &limits, 100).to_string()
+        self.star1_with(input, &Params::default())
     }
 
     fn star2(&self, input: &str) -> String {
+        self.star2_with(input, &Params::default())
+    }
+
+    fn star1_with(&self, input: &str, params: &Params) -> String {
         let robots = parse_input(input);
-        let limits = Coords { x: 101, y: 103 };
+        safety_factor(&robots, &limits(params), 100).to_string()
+    }
+
+    fn star2_with(&self, input: &str, params: &Params) -> String {
+        let robots = parse_input(input);
+        let limits = limits(params);
         let after = smallest_cluster(&robots, &limits);
         // print_pattern(&robots, &limits, after);
         after.to_string()
     }
+
+    fn params(&self) -> Vec<ParamInfo> {
+        vec![WIDTH.info(), HEIGHT.info()]
+    }
+}
+
+fn limits(params: &Params) -> Coords {
+    Coords {
+        x: params.get(&WIDTH),
+        y: params.get(&HEIGHT),
+    }
 }
 
 struct Robot {
@@ -127,28 +149,3 @@ fn print_pattern(robots: &[Robot], limits: &Coords, after: i64) {
 
     println!("{}", grid);
 }
-
-#[cfg(test)]
-mod tests {
-    use super::*;
-
-    const INPUT: &str = r#"p=0,4 v=3,-3
-p=6,3 v=-1,-3
-p=10,3 v=-1,2
-p=2,0 v=2,-1
-p=0,0 v=1,3
-p=3,0 v=-2,-2
-p=7,6 v=-1,-3
-p=3,0 v=-1,-2
-p=9,3 v=2,3
-p=7,3 v=-1,2
-p=2,4 v=2,-3
-p=9,5 v=-3,-3"#;
-
-    #[test]
-    fn star1() {
-        let robots = parse_input(INPUT);
-        let limits = Coords { x: 11, y: 7 };
-        assert_eq!(safety_factor(&robots, &limits, 100), 12);
-    }
-}
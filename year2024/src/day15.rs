@@ -1,5 +1,6 @@
 use common::day::Day;
 use util::grid2d::{Coords, Direction, Grid2D};
+use util::viz;
 
 pub struct Day15 {}
 
@@ -63,10 +64,29 @@ fn execute_moves(grid: &mut Grid2D<Field>, dirs: &[Direction]) -> i64 {
         if can_push(grid, &cur_pos, *dir) {
             cur_pos = push(grid, &cur_pos, *dir);
         }
+        if viz::is_enabled() {
+            viz::frame(&to_chars(grid));
+        }
     }
     gps_sum(grid)
 }
 
+fn to_chars(grid: &Grid2D<Field>) -> Grid2D<char> {
+    let mut chars = Grid2D::with_default(grid.dimensions(), &'.');
+    for (pos, value) in grid.enumerate() {
+        let c = match value {
+            Field::Empty => '.',
+            Field::Wall => '#',
+            Field::Robot => '@',
+            Field::Box => 'O',
+            Field::BoxLeft => '[',
+            Field::BoxRight => ']',
+        };
+        chars.set(&pos, c);
+    }
+    chars
+}
+
 fn can_push(grid: &Grid2D<Field>, cur_pos: &Coords, dir: Direction) -> bool {
     let new_pos = cur_pos.mov(dir);
     match grid.at(&new_pos).unwrap() {
@@ -1,22 +1,44 @@
 use std::collections::{BinaryHeap, HashMap};
 
 use common::day::Day;
+use common::params::{Param, ParamInfo, Params};
 use util::grid2d::{Coords, Grid2D};
 
 pub struct Day18 {}
 
+const SIZE: Param<i64> = Param::new("size", 71);
+const BYTES: Param<usize> = Param::new("bytes", 1024);
+
 impl Day for Day18 {
     fn star1(&self, input: &str) -> String {
+        self.star1_with(input, &Params::default())
+    }
+
+    fn star2(&self, input: &str) -> String {
+        self.star2_with(input, &Params::default())
+    }
+
+    fn star1_with(&self, input: &str, params: &Params) -> String {
         let bytes = parse_input(input);
-        let grid = grid_after(Coords { x: 71, y: 71 }, &bytes, 1024);
+        let grid = grid_after(dims(params), &bytes, params.get(&BYTES));
         search(&grid).unwrap().to_string()
     }
 
-    fn star2(&self, input: &str) -> String {
+    fn star2_with(&self, input: &str, params: &Params) -> String {
         let bytes = parse_input(input);
-        let byte = first_blocker(Coords { x: 71, y: 71 }, &bytes);
+        let byte = first_blocker(dims(params), &bytes);
         format!("{},{}", byte.x, byte.y)
     }
+
+    fn params(&self) -> Vec<ParamInfo> {
+        vec![SIZE.info(), BYTES.info()]
+    }
+}
+
+// the memory space is square
+fn dims(params: &Params) -> Coords {
+    let size = params.get(&SIZE);
+    Coords { x: size, y: size }
 }
 
 #[derive(Copy, Clone, Default, PartialEq, Eq)]
@@ -128,50 +150,3 @@ fn first_blocker(dims: Coords, bytes: &[Coords]) -> Coords {
     }
     bytes[low - 1]
 }
-
-#[cfg(test)]
-mod tests {
-    use super::*;
-
-    const INPUT: &str = r#"5,4
-4,2
-4,5
-3,0
-2,1
-6,3
-2,4
-1,5
-0,6
-3,3
-2,6
-5,1
-1,2
-5,5
-2,5
-6,5
-1,4
-0,4
-6,4
-1,1
-6,1
-1,0
-0,5
-1,6
-2,0"#;
-
-    #[test]
-    fn star1() {
-        let bytes = parse_input(INPUT);
-        let grid = grid_after(Coords { x: 7, y: 7 }, &bytes, 12);
-        assert_eq!(search(&grid), Some(22));
-    }
-
-    #[test]
-    fn star2() {
-        let bytes = parse_input(INPUT);
-        assert_eq!(
-            first_blocker(Coords { x: 7, y: 7 }, &bytes),
-            Coords { x: 6, y: 1 }
-        );
-    }
-}
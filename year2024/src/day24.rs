@@ -2,96 +2,93 @@ use std::collections::{BTreeSet, HashMap};
 
 use common::day::Day;
 use itertools::Itertools;
+use util::expr::{Circuit, Node, Operand, Operator};
 
 pub struct Day24 {}
 
 impl Day for Day24 {
     fn star1(&self, input: &str) -> String {
-        let (gates, mut values) = parse_input(input);
-        output_number(&gates, &mut values).to_string()
+        let gates = parse_input(input);
+        output_number(&gates).to_string()
     }
 
     fn star2(&self, input: &str) -> String {
-        let (gates, _) = parse_input(input);
+        let gates = parse_input(input);
         let swapped = swapped_gates(&gates);
         swapped.iter().join(",")
     }
 }
 
-type Gates<'a> = HashMap<&'a str, Gate<'a>>;
-type Values<'a> = HashMap<&'a str, bool>;
+type Gates<'a> = Circuit<&'a str, bool, Op>;
 
-#[derive(PartialEq, Eq, Hash)]
-struct Gate<'a> {
-    pub input: (&'a str, &'a str),
-    pub op: Op,
-}
-
-#[derive(PartialEq, Eq, Hash)]
+#[derive(Copy, Clone, PartialEq, Eq, Hash)]
 enum Op {
     And,
     Or,
     Xor,
 }
 
-fn solve<'a>(gate_id: &'a str, gates: &'a Gates, values: &mut Values<'a>) -> bool {
-    if let Some(v) = values.get(gate_id) {
-        return *v;
+impl Operator<bool> for Op {
+    fn apply(&self, args: &[bool]) -> bool {
+        match self {
+            Op::And => args[0] & args[1],
+            Op::Or => args[0] | args[1],
+            Op::Xor => args[0] ^ args[1],
+        }
     }
-
-    let gate = gates.get(gate_id).unwrap();
-    let i1 = solve(gate.input.0, gates, values);
-    let i2 = solve(gate.input.1, gates, values);
-
-    let out = match gate.op {
-        Op::And => i1 & i2,
-        Op::Or => i1 | i2,
-        Op::Xor => i1 ^ i2,
-    };
-    values.insert(gate_id, out);
-    out
 }
 
-fn parse_input(input: &str) -> (Gates<'_>, Values<'_>) {
-    let mut secs = input.split("\n\n");
+struct Gate<'a> {
+    pub input: (&'a str, &'a str),
+    pub op: Op,
+}
 
-    let values = secs
-        .next()
-        .unwrap()
-        .lines()
-        .map(|line| {
-            let parts: Vec<_> = line.split(": ").collect();
-            (parts[0], parts[1].starts_with("1"))
-        })
-        .collect();
+fn gate<'a>(node: &Node<&'a str, bool, Op>) -> Option<Gate<'a>> {
+    match node {
+        Node::Op(op, _) => {
+            let mut refs = node.refs();
+            let input = (*refs.next()?, *refs.next()?);
+            Some(Gate { input, op: *op })
+        }
+        Node::Value(_) => None,
+    }
+}
 
-    let gates = secs
-        .next()
-        .unwrap()
-        .lines()
-        .map(|line| {
-            let parts: Vec<_> = line.split_whitespace().collect();
-            let output = parts[4];
-            let input = (parts[0], parts[2]);
-            let op = match parts[1] {
-                "AND" => Op::And,
-                "OR" => Op::Or,
-                "XOR" => Op::Xor,
-                _ => unreachable!(),
-            };
-            (output, Gate { input, op })
-        })
-        .collect();
+fn parse_input(input: &str) -> Gates<'_> {
+    let mut secs = input.split("\n\n");
 
-    (gates, values)
+    let values = secs.next().unwrap().lines().map(|line| {
+        let parts: Vec<_> = line.split(": ").collect();
+        (parts[0], Node::Value(parts[1].starts_with("1")))
+    });
+
+    let gates = secs.next().unwrap().lines().map(|line| {
+        let parts: Vec<_> = line.split_whitespace().collect();
+        let output = parts[4];
+        let input = vec![Operand::Ref(parts[0]), Operand::Ref(parts[2])];
+        let op = match parts[1] {
+            "AND" => Op::And,
+            "OR" => Op::Or,
+            "XOR" => Op::Xor,
+            _ => unreachable!(),
+        };
+        (output, Node::Op(op, input))
+    });
+
+    values.chain(gates).collect()
 }
 
-fn output_number<'a>(gates: &'a Gates, values: &mut Values<'a>) -> usize {
+fn output_number(gates: &Gates) -> usize {
     let mut output = 0;
+    let mut values = HashMap::new();
 
-    for z_gate in gates.keys().filter(|key| key.starts_with('z')) {
+    for z_gate in gates
+        .iter()
+        .map(|(k, _)| k)
+        .filter(|key| key.starts_with('z'))
+    {
         let index: usize = z_gate[1..].parse().unwrap();
-        let value = solve(z_gate, gates, values);
+        let value = gates.eval_with(z_gate, &mut values).unwrap();
 
         if value {
             output += 1 << index;
@@ -103,6 +100,10 @@ fn output_number<'a>(gates: &'a Gates, values: &mut Values<'a>) -> usize {
 
 fn swapped_gates<'a>(gates: &Gates<'a>) -> BTreeSet<&'a str> {
     let mut swapped = BTreeSet::new();
+    let gates: HashMap<_, _> = gates
+        .iter()
+        .filter_map(|(output, node)| gate(node).map(|g| (*output, g)))
+        .collect();
 
     let z_max: usize = gates
         .keys()
@@ -122,7 +123,7 @@ fn swapped_gates<'a>(gates: &Gates<'a>) -> BTreeSet<&'a str> {
         }
     }
 
-    for (&output, gate) in gates {
+    for (&output, gate) in &gates {
         match gate.op {
             Op::Xor => {
                 // - XOR gates must be either connected to input (x,y) or output
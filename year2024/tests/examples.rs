@@ -0,0 +1,8 @@
+use common::year::Year;
+use std::process::ExitCode;
+use year2024::Year2024;
+
+// Runs the fixtures in examples/year2024.
+fn main() -> ExitCode {
+    common::fixture::harness(2024, |day_no| Year2024 {}.get_day(day_no))
+}
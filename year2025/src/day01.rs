@@ -10,6 +10,10 @@ impl Day for Day01 {
     fn star2(&self, _input: &str) -> String {
         String::from("not implemented")
     }
+
+    fn stars(&self) -> usize {
+        0
+    }
 }
 
 #[cfg(test)]
@@ -0,0 +1,8 @@
+use common::year::Year;
+use std::process::ExitCode;
+use year2025::Year2025;
+
+// Runs the fixtures in examples/year2025.
+fn main() -> ExitCode {
+    common::fixture::harness(2025, |day_no| Year2025 {}.get_day(day_no))
+}